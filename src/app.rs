@@ -1,30 +1,61 @@
-use std::{error, fmt};
+use std::{error, fmt, fs, path, time};
 
 use crate::{
-    config::{APPLICATION_ID, APPLICATION_TITLE, Config, ConfigError},
+    chord::{Chord, ChordCandidate, ChordQuality},
+    config::{
+        APPLICATION_ID,
+        APPLICATION_TITLE,
+        Config,
+        ConfigArgs,
+        ConfigDocument,
+        ConfigError,
+        ConfigIssue,
+        DocumentTuning,
+    },
+    render::RenderError,
+    scale::{Scale, ScaleKind},
+    session::{Session, SessionWindow},
+    tension::{StringTension, UnitWeights},
     theme::ThemeName,
-    tuning::{NoteFormat, Tuning},
-    widget::Fretboard,
+    tuning::{Capo, FretPosition, Note, NoteFormat, Pitch, ScaleLength, StringGauge, StringRange, Tuning},
+    widget::{Fretboard, LabelMode, Orientation},
 };
 
 const DEFAULT_PADDING: iced::Pixels = iced::Pixels(10.0);
+const EXPORT_WIDTH: f32 = 1600.0;
+const CONFIG_POLL_INTERVAL: time::Duration = time::Duration::from_secs(1);
+const EDITOR_HEIGHT: f32 = 280.0;
+const TENSION_WIDTH: f32 = 300.0;
 
-pub fn run() -> Result<(), AppError> {
+pub fn run(args: ConfigArgs) -> Result<(), AppError> {
+    let session = Session::read().unwrap_or_else(|err| {
+        log::error!("Could not read session: {}", err);
+        Session::default()
+    });
     let mut window_settings = iced::window::Settings::default();
     window_settings.platform_specific.application_id = String::from(APPLICATION_ID);
-    let app = iced::application(boot, update, view)
+    window_settings.exit_on_close_request = false;
+    if let Some(window) = session.window {
+        window_settings.size = iced::Size::new(window.width as f32, window.height as f32);
+        if let (Some(x), Some(y)) = (window.x, window.y) {
+            window_settings.position = iced::window::Position::Specific(iced::Point::new(x as f32, y as f32));
+        }
+    }
+    let app = iced::application(move || boot(args.clone(), session.clone()), update, view)
         .window(window_settings)
-        .title(|state: &State| match state {
-            State::Running(data) => match &data.tuning.selected {
+        .subscription(subscription)
+        .title(|state: &State| match (&state.config_error, &state.data) {
+            (Some(_), _) => format!("{APPLICATION_TITLE} - Configuration Error"),
+            (None, Some(data)) => match &data.tuning.selected {
                 Some(tuning) => format!("{APPLICATION_TITLE} - {tuning}"),
                 None => String::from(APPLICATION_TITLE),
             },
-            State::ConfigError(_) => format!("{APPLICATION_TITLE} - Configuration Error"),
+            (None, None) => String::from(APPLICATION_TITLE),
         })
         .theme(|state: &State| {
-            iced::Theme::from(match state {
-                State::Running(data) => data.theme_name,
-                State::ConfigError(_) => ThemeName::default(),
+            iced::Theme::from(match &state.data {
+                Some(data) => data.theme_name,
+                None => ThemeName::default(),
             })
         });
     app.run()?;
@@ -33,9 +64,25 @@ pub fn run() -> Result<(), AppError> {
 
 #[derive(Debug)]
 pub enum AppError {
+    Render(RenderError),
     Ui(iced::Error),
 }
 
+impl AppError {
+    pub fn format_report(&self) -> Option<String> {
+        match self {
+            Self::Render(err) => err.format_report(),
+            Self::Ui(_) => None,
+        }
+    }
+}
+
+impl From<RenderError> for AppError {
+    fn from(value: RenderError) -> Self {
+        Self::Render(value)
+    }
+}
+
 impl From<iced::Error> for AppError {
     fn from(value: iced::Error) -> Self {
         Self::Ui(value)
@@ -45,6 +92,7 @@ impl From<iced::Error> for AppError {
 impl fmt::Display for AppError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::Render(err) => err.fmt(out),
             Self::Ui(err) => err.fmt(out),
         }
     }
@@ -53,119 +101,1357 @@ impl fmt::Display for AppError {
 impl error::Error for AppError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         Some(match self {
+            Self::Render(err) => err,
             Self::Ui(err) => err,
         })
     }
 }
 
 #[derive(Debug)]
-enum State {
-    Running(StateData),
-    ConfigError(ConfigError),
+struct State {
+    config: StateConfig,
+    config_error: Option<ConfigError>,
+    /// The last working state, kept when a reloaded configuration is invalid.
+    data: Option<StateData>,
+    window: SessionWindow,
+}
+
+impl State {
+    fn reload(&mut self) {
+        match Config::read(&self.config.args) {
+            Ok(config) => {
+                log::info!("Configuration reloaded");
+                match &mut self.data {
+                    Some(data) => data.apply_config(config),
+                    None => self.data = Some(StateData::new(config)),
+                }
+                self.config_error = None;
+            }
+            Err(err) => {
+                log::error!("Could not reload configuration: {}", err);
+                self.config_error = Some(err);
+            }
+        }
+    }
+
+    fn save_session(&self) {
+        let mut session = match &self.data {
+            Some(data) => data.create_session(),
+            None => Session::read().unwrap_or_default(),
+        };
+        session.window = Some(self.window);
+        if let Err(err) = session.write() {
+            log::error!("Could not save session: {}", err);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StateConfig {
+    args: ConfigArgs,
+    modified: Option<time::SystemTime>,
+    path: Option<path::PathBuf>,
+}
+
+impl StateConfig {
+    fn new(args: ConfigArgs) -> Self {
+        let path = Config::get_path(&args);
+        let modified = path.as_deref().and_then(Self::read_modified);
+        Self { args, modified, path }
+    }
+
+    fn poll(&mut self) -> bool {
+        let modified = self.path.as_deref().and_then(Self::read_modified);
+        if modified == self.modified {
+            false
+        } else {
+            self.modified = modified;
+            true
+        }
+    }
+
+    fn read_modified(path: &path::Path) -> Option<time::SystemTime> {
+        fs::metadata(path).and_then(|x| x.modified()).ok()
+    }
 }
 
 #[derive(Debug)]
 struct StateData {
+    chord: StateChord,
+    /// Options of the last loaded configuration, a reload applies only the ones changed in the file.
+    config_options: StateConfigOptions,
+    editor: Option<StateEditor>,
+    export: StateExport,
+    flip_strings: bool,
+    hide_unhighlighted: bool,
+    label_mode: LabelMode,
+    left_handed: bool,
     note_format: NoteFormat,
+    orientation: Orientation,
+    scale: StateScale,
+    selection: Vec<FretPosition>,
+    show_tension: bool,
     theme_name: ThemeName,
     tuning: StateTuning,
+    unit_weights: UnitWeights,
+    warnings: Vec<ConfigIssue>,
+}
+
+#[derive(Debug)]
+struct StateChord {
+    input: String,
+    selected: Option<Chord>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct StateConfigOptions {
+    flip_strings: bool,
+    left_handed: bool,
+    note_format: NoteFormat,
+    orientation: Orientation,
+    theme_name: ThemeName,
+}
+
+impl StateConfigOptions {
+    fn new(config: &Config) -> Self {
+        Self {
+            flip_strings: config.flip_strings,
+            left_handed: config.left_handed,
+            note_format: config.note_format,
+            orientation: config.orientation,
+            theme_name: config.theme_name,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StateEditor {
+    capo_input: String,
+    capo_strings_input: String,
+    document: ConfigDocument,
+    frets_input: String,
+    gauges_input: String,
+    scale_length_input: String,
+    selected: Option<usize>,
+    status: Option<String>,
+    tunings: Vec<DocumentTuning>,
+}
+
+impl StateEditor {
+    fn new(document: ConfigDocument, selected_name: Option<&str>) -> Self {
+        let tunings = document.get_tunings();
+        let selected = selected_name
+            .and_then(|name| tunings.iter().position(|x| x.get().name == name))
+            .or((!tunings.is_empty()).then_some(0));
+        let mut result = Self {
+            capo_input: String::new(),
+            capo_strings_input: String::new(),
+            document,
+            frets_input: String::new(),
+            gauges_input: String::new(),
+            scale_length_input: String::new(),
+            selected: None,
+            status: None,
+            tunings,
+        };
+        result.select(selected);
+        result
+    }
+
+    fn get_selected(&self) -> Option<&Tuning> {
+        self.selected
+            .and_then(|idx| self.tunings.get(idx))
+            .map(DocumentTuning::get)
+    }
+
+    fn get_selected_mut(&mut self) -> Option<&mut Tuning> {
+        self.selected
+            .and_then(|idx| self.tunings.get_mut(idx))
+            .map(DocumentTuning::get_mut)
+    }
+
+    fn select(&mut self, idx: Option<usize>) {
+        self.selected = idx.filter(|x| *x < self.tunings.len());
+        let capo = self.get_selected().and_then(|x| x.capo);
+        self.capo_input = capo.map(|x| x.fret.to_string()).unwrap_or_default();
+        self.capo_strings_input = capo.and_then(|x| x.strings).map(|x| x.to_string()).unwrap_or_default();
+        self.frets_input = self
+            .get_selected()
+            .map(|x| x.total_frets.to_string())
+            .unwrap_or_default();
+        self.reset_gauges_input();
+        self.scale_length_input = self
+            .get_selected()
+            .and_then(|x| x.scale_length)
+            .map(|x| match x.is_fanned() {
+                true => format!("{} {}", x.bass, x.treble),
+                false => x.bass.to_string(),
+            })
+            .unwrap_or_default();
+    }
+
+    fn update_capo(&mut self) -> bool {
+        let fret = match self.capo_input.trim() {
+            "" => Some(0),
+            x => x.parse::<u8>().ok(),
+        };
+        let strings = match self.capo_strings_input.trim() {
+            "" => Some(None),
+            x => x.parse::<StringRange>().ok().map(Some),
+        };
+        let (Some(fret), Some(strings)) = (fret, strings) else {
+            return false;
+        };
+        if let Some(tuning) = self.get_selected_mut() {
+            tuning.capo = (fret > 0).then_some(Capo { fret, strings });
+        }
+        true
+    }
+
+    fn reset_gauges_input(&mut self) {
+        self.gauges_input = self
+            .get_selected()
+            .map(|x| x.gauges.iter().map(ToString::to_string).collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+    }
+}
+
+#[derive(Debug)]
+struct StateExport {
+    path: String,
+    status: Option<String>,
+}
+
+#[derive(Debug)]
+struct StateScale {
+    selected: Scale,
 }
 
 #[derive(Debug)]
 struct StateTuning {
+    capo_strings_input: String,
     combo_box: iced::widget::combo_box::State<Tuning>,
+    instrument: InstrumentChoice,
+    items: Vec<Tuning>,
     selected: Option<Tuning>,
 }
 
+impl StateTuning {
+    fn new(items: Vec<Tuning>, selected: Tuning) -> Self {
+        let mut result = Self {
+            capo_strings_input: String::new(),
+            combo_box: iced::widget::combo_box::State::new(Vec::new()),
+            instrument: InstrumentChoice::All,
+            items,
+            selected: None,
+        };
+        result.select(selected);
+        result
+    }
+
+    fn select(&mut self, tuning: Tuning) {
+        self.capo_strings_input = format_capo_strings(tuning.capo);
+        self.select_instrument(InstrumentChoice::of(&tuning));
+        self.selected = Some(tuning);
+    }
+
+    fn select_instrument(&mut self, instrument: InstrumentChoice) -> Option<Tuning> {
+        let tunings: Vec<Tuning> = self.items.iter().filter(|x| instrument.matches(x)).cloned().collect();
+        let result = match &self.selected {
+            Some(selected) if tunings.iter().any(|x| x.name == selected.name) => None,
+            _ => tunings.first().cloned(),
+        };
+        self.combo_box = iced::widget::combo_box::State::new(tunings);
+        self.instrument = instrument;
+        result
+    }
+}
+
 impl StateData {
+    fn apply_session(&mut self, session: Session, args: &ConfigArgs) {
+        if args.tuning.is_none()
+            && let Some(tuning) = session
+                .tuning
+                .and_then(|name| self.tuning.items.iter().find(|x| x.name == name).cloned())
+        {
+            self.tuning.select(tuning);
+        }
+        if args.note_format.is_none() {
+            self.note_format = session.note_format.unwrap_or(self.note_format);
+        }
+        if args.theme.is_none() {
+            self.theme_name = session.theme_name.unwrap_or(self.theme_name);
+        }
+        if args.orientation.is_none() {
+            self.orientation = session.orientation.unwrap_or(self.orientation);
+        }
+        self.label_mode = session.label_mode.unwrap_or(self.label_mode);
+        self.left_handed = session.left_handed.unwrap_or(self.left_handed);
+        self.flip_strings = session.flip_strings.unwrap_or(self.flip_strings);
+        self.hide_unhighlighted = session.hide_unhighlighted.unwrap_or(self.hide_unhighlighted);
+        self.show_tension = session.show_tension.unwrap_or(self.show_tension);
+    }
+
+    fn create_session(&self) -> Session {
+        Session {
+            tuning: self.tuning.selected.as_ref().map(|x| x.name.clone()),
+            note_format: Some(self.note_format),
+            theme_name: Some(self.theme_name),
+            orientation: Some(self.orientation),
+            label_mode: Some(self.label_mode),
+            left_handed: Some(self.left_handed),
+            flip_strings: Some(self.flip_strings),
+            hide_unhighlighted: Some(self.hide_unhighlighted),
+            show_tension: Some(self.show_tension),
+            window: None,
+        }
+    }
+
+    fn apply_config(&mut self, config: Config) {
+        let options = StateConfigOptions::new(&config);
+        let tuning_kept = self
+            .tuning
+            .selected
+            .as_ref()
+            .and_then(|selected| config.tuning.items.iter().find(|x| x.name == selected.name))
+            .cloned();
+        match &tuning_kept {
+            Some(tuning) => self
+                .selection
+                .retain(|x| tuning.get_pitch(*x).is_some() && !tuning.is_behind_capo(*x)),
+            None => self.selection.clear(),
+        }
+        let tuning_selected = tuning_kept.unwrap_or_else(|| config.tuning.get_selected().clone());
+        self.tuning = StateTuning::new(config.tuning.items, tuning_selected);
+        if options.flip_strings != self.config_options.flip_strings {
+            self.flip_strings = options.flip_strings;
+        }
+        if options.left_handed != self.config_options.left_handed {
+            self.left_handed = options.left_handed;
+        }
+        if options.note_format != self.config_options.note_format {
+            self.note_format = options.note_format;
+        }
+        if options.orientation != self.config_options.orientation {
+            self.orientation = options.orientation;
+        }
+        if options.theme_name != self.config_options.theme_name {
+            self.theme_name = options.theme_name;
+        }
+        self.config_options = options;
+        self.unit_weights = config.unit_weights;
+        self.warnings = config.warnings;
+    }
+
     fn new(config: Config) -> Self {
         let tuning_selected = config.tuning.get_selected().clone();
-        let tuning = config.tuning.items.clone();
         Self {
+            chord: StateChord {
+                input: String::new(),
+                selected: None,
+            },
+            config_options: StateConfigOptions::new(&config),
+            editor: None,
+            export: StateExport {
+                path: String::from("fretboard.svg"),
+                status: None,
+            },
+            flip_strings: config.flip_strings,
+            hide_unhighlighted: false,
+            label_mode: LabelMode::default(),
+            left_handed: config.left_handed,
             note_format: config.note_format,
-            theme_name: config.theme_name,
-            tuning: StateTuning {
-                combo_box: iced::widget::combo_box::State::new(tuning),
-                selected: Some(tuning_selected),
+            orientation: config.orientation,
+            scale: StateScale {
+                selected: Scale::new(Note::C, ScaleKind::default()),
             },
+            selection: Vec::new(),
+            show_tension: false,
+            theme_name: config.theme_name,
+            tuning: StateTuning::new(config.tuning.items, tuning_selected),
+            unit_weights: config.unit_weights,
+            warnings: config.warnings,
         }
     }
 }
 
 #[derive(Clone, Debug)]
 enum Message {
+    CapoFretSelected(CapoChoice),
+    CapoStringsChanged(String),
+    ChordCleared,
+    ChordInputChanged(String),
+    ChordQualitySelected(ChordQuality),
+    ConfigDefaultsUsed,
+    ConfigPolled,
+    ConfigReloaded,
+    Editor(EditorMessage),
+    ExportFinished(String, Result<(), String>),
+    ExportPathChanged(String),
+    Exported,
+    InstrumentSelected(InstrumentChoice),
+    LabelModeSelected(LabelMode),
+    LeftHandedToggled(bool),
     NoteFormatSelected(NoteFormat),
+    OrientationSelected(Orientation),
+    PositionToggled(FretPosition),
+    ScaleKindSelected(ScaleKind),
+    ScaleRootSelected(Note),
+    SelectionCleared,
+    StringsFlipped(bool),
+    TensionShown(bool),
+    ThemeSelected(ThemeName),
     TuningSelected(Tuning),
+    UnhighlightedHidden(bool),
+    WarningsDismissed,
+    WindowCloseRequested(iced::window::Id),
+    WindowMoved(iced::Point),
+    WindowResized(iced::Size),
+}
+
+#[derive(Clone, Debug)]
+enum EditorMessage {
+    CapoChanged(String),
+    CapoStringsChanged(String),
+    Closed,
+    FretsChanged(String),
+    GaugesChanged(String),
+    Opened,
+    Saved,
+    ScaleLengthChanged(String),
+    StringAdded,
+    StringRemoved(usize),
+    StringTransposed(usize, i16),
+    TuningAdded,
+    TuningDeleted,
+    TuningDuplicated,
+    TuningMovedDown,
+    TuningMovedUp,
+    TuningRenamed(String),
+    TuningSelected(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NoteChoice {
+    note: Note,
+    note_format: NoteFormat,
+}
+
+impl NoteChoice {
+    fn list(note_format: NoteFormat) -> Vec<Self> {
+        Note::ALL.into_iter().map(|note| Self { note, note_format }).collect()
+    }
+}
+
+impl fmt::Display for NoteChoice {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "{}", self.note.format(self.note_format))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum InstrumentChoice {
+    All,
+    Instrument(String),
+    Other,
+}
+
+impl InstrumentChoice {
+    fn list(tunings: &[Tuning]) -> Vec<Self> {
+        let mut result = vec![Self::All];
+        for tuning in tunings {
+            let choice = Self::of(tuning);
+            if choice != Self::Other && !result.contains(&choice) {
+                result.push(choice);
+            }
+        }
+        if tunings.iter().any(|x| x.instrument.is_none()) {
+            result.push(Self::Other);
+        }
+        result
+    }
+
+    fn of(tuning: &Tuning) -> Self {
+        match &tuning.instrument {
+            Some(name) => Self::Instrument(name.clone()),
+            None => Self::Other,
+        }
+    }
+
+    fn matches(&self, tuning: &Tuning) -> bool {
+        match self {
+            Self::All => true,
+            Self::Instrument(name) => tuning.instrument.as_ref() == Some(name),
+            Self::Other => tuning.instrument.is_none(),
+        }
+    }
+}
+
+impl fmt::Display for InstrumentChoice {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::All => write!(out, "All instruments"),
+            Self::Instrument(name) => write!(out, "{}", name),
+            Self::Other => write!(out, "Other tunings"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CapoChoice(u8);
+
+impl CapoChoice {
+    fn list(total_frets: u8) -> Vec<Self> {
+        (0..=total_frets).map(Self).collect()
+    }
+}
+
+impl fmt::Display for CapoChoice {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            0 => write!(out, "No capo"),
+            fret => write!(out, "Capo {}", fret),
+        }
+    }
 }
 
-fn boot() -> State {
-    match Config::read_from_file() {
-        Ok(config) => State::Running(StateData::new(config)),
+fn format_capo_strings(capo: Option<Capo>) -> String {
+    capo.and_then(|x| x.strings).map(|x| x.to_string()).unwrap_or_default()
+}
+
+fn boot(args: ConfigArgs, session: Session) -> State {
+    let config = StateConfig::new(args);
+    let window = session.window.unwrap_or_else(|| {
+        let size = iced::window::Settings::default().size;
+        SessionWindow {
+            width: size.width as u32,
+            height: size.height as u32,
+            x: None,
+            y: None,
+        }
+    });
+    match Config::read(&config.args) {
+        Ok(data) => {
+            let mut data = StateData::new(data);
+            data.apply_session(session, &config.args);
+            State {
+                config,
+                config_error: None,
+                data: Some(data),
+                window,
+            }
+        }
         Err(err) => {
-            eprintln!("{:?}", err);
-            State::ConfigError(err)
+            eprintln!("{}", err.format_report().unwrap_or_else(|| err.to_string()));
+            State {
+                config,
+                config_error: Some(err),
+                data: None,
+                window,
+            }
         }
     }
 }
 
-fn update(state: &mut State, message: Message) {
-    let State::Running(state_data) = state else {
-        return;
+fn subscription(state: &State) -> iced::Subscription<Message> {
+    let window = iced::event::listen_with(|event, _, id| match event {
+        iced::Event::Window(iced::window::Event::CloseRequested) => Some(Message::WindowCloseRequested(id)),
+        iced::Event::Window(iced::window::Event::Moved(position)) => Some(Message::WindowMoved(position)),
+        iced::Event::Window(iced::window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+        _ => None,
+    });
+    match state.config.path {
+        Some(_) => iced::Subscription::batch([
+            window,
+            iced::time::every(CONFIG_POLL_INTERVAL).map(|_| Message::ConfigPolled),
+        ]),
+        None => window,
+    }
+}
+
+fn update(state: &mut State, message: Message) -> iced::Task<Message> {
+    match message {
+        Message::ConfigDefaultsUsed => {
+            // The last working state is kept, the defaults are only used when there is none.
+            if state.data.is_none() {
+                log::warn!("Using default configuration");
+                let config = Config::default().with_args(&state.config.args).unwrap_or_else(|err| {
+                    log::error!("Could not apply command line options: {}", err);
+                    Config::default()
+                });
+                let mut data = StateData::new(config);
+                data.apply_session(Session::read().unwrap_or_default(), &state.config.args);
+                state.data = Some(data);
+            } else {
+                log::warn!("Using the last working configuration");
+            }
+            state.config_error = None;
+            return iced::Task::none();
+        }
+        Message::ConfigPolled => {
+            if state.config.poll() {
+                state.reload();
+            }
+            return iced::Task::none();
+        }
+        Message::ConfigReloaded => {
+            state.reload();
+            return iced::Task::none();
+        }
+        Message::WindowCloseRequested(id) => {
+            state.save_session();
+            return iced::window::close(id);
+        }
+        Message::WindowMoved(position) => {
+            state.window.x = Some(position.x.round() as i32);
+            state.window.y = Some(position.y.round() as i32);
+            return iced::Task::none();
+        }
+        Message::WindowResized(size) => {
+            state.window.width = size.width.round() as u32;
+            state.window.height = size.height.round() as u32;
+            return iced::Task::none();
+        }
+        _ => {}
+    }
+    if state.config_error.is_some() {
+        return iced::Task::none();
+    }
+    let Some(state_data) = &mut state.data else {
+        return iced::Task::none();
     };
     match message {
+        Message::CapoFretSelected(CapoChoice(fret)) => {
+            if let Some(tuning) = &mut state_data.tuning.selected {
+                let strings = state_data.tuning.capo_strings_input.parse::<StringRange>().ok();
+                tuning.capo = (fret > 0).then_some(Capo { fret, strings });
+                state_data.selection.retain(|x| !tuning.is_behind_capo(*x));
+            }
+        }
+        Message::CapoStringsChanged(value) => {
+            if let Some(tuning) = &mut state_data.tuning.selected
+                && let Some(capo) = &mut tuning.capo
+            {
+                if value.trim().is_empty() {
+                    capo.strings = None;
+                } else if let Ok(strings) = value.parse::<StringRange>() {
+                    capo.strings = Some(strings);
+                }
+                state_data.selection.retain(|x| !tuning.is_behind_capo(*x));
+            }
+            state_data.tuning.capo_strings_input = value;
+        }
+        Message::ChordCleared => {
+            state_data.chord.input.clear();
+            state_data.chord.selected = None;
+        }
+        Message::ChordInputChanged(value) => {
+            state_data.chord.selected = value.parse::<Chord>().ok();
+            state_data.chord.input = value;
+        }
+        Message::ChordQualitySelected(quality) => {
+            let chord = match state_data.chord.selected.take() {
+                Some(chord) => Chord { quality, ..chord },
+                None => Chord::new(state_data.scale.selected.root, quality),
+            };
+            state_data.chord.input = chord.format(state_data.note_format);
+            state_data.chord.selected = Some(chord);
+        }
+        // Handled before the configuration error check.
+        Message::ConfigDefaultsUsed
+        | Message::ConfigPolled
+        | Message::ConfigReloaded
+        | Message::WindowCloseRequested(_)
+        | Message::WindowMoved(_)
+        | Message::WindowResized(_) => {}
+        Message::Editor(message) => update_editor(state_data, &state.config.args, message),
+        Message::ExportFinished(path, result) => {
+            state_data.export.status = Some(match result {
+                Ok(()) => {
+                    log::info!("Exported SVG to {}", path);
+                    format!("Exported to {}", path)
+                }
+                Err(err) => {
+                    log::error!("Could not export SVG to {}: {}", path, err);
+                    format!("Export failed: {}", err)
+                }
+            });
+        }
+        Message::ExportPathChanged(value) => state_data.export.path = value,
+        Message::Exported => {
+            let Some(fretboard) = create_fretboard(state_data) else {
+                return iced::Task::none();
+            };
+            let svg = fretboard.to_svg(fretboard.get_natural_size(EXPORT_WIDTH));
+            let path = state_data.export.path.clone();
+            state_data.export.status = Some(format!("Exporting to {}", path));
+            return iced::Task::perform(
+                async move {
+                    let result = fs::write(&path, svg).map_err(|err| err.to_string());
+                    (path, result)
+                },
+                |(path, result)| Message::ExportFinished(path, result),
+            );
+        }
+        Message::InstrumentSelected(instrument) => {
+            if let Some(tuning) = state_data.tuning.select_instrument(instrument) {
+                state_data.selection.clear();
+                state_data.tuning.capo_strings_input = format_capo_strings(tuning.capo);
+                state_data.tuning.selected = Some(tuning);
+            }
+        }
+        Message::LabelModeSelected(label_mode) => state_data.label_mode = label_mode,
+        Message::LeftHandedToggled(value) => state_data.left_handed = value,
         Message::NoteFormatSelected(note_format) => state_data.note_format = note_format,
-        Message::TuningSelected(tuning) => state_data.tuning.selected = Some(tuning),
+        Message::OrientationSelected(orientation) => state_data.orientation = orientation,
+        Message::PositionToggled(position) => match state_data.selection.iter().position(|x| *x == position) {
+            Some(idx) => {
+                state_data.selection.remove(idx);
+            }
+            None => state_data.selection.push(position),
+        },
+        Message::ScaleKindSelected(kind) => state_data.scale.selected.kind = kind,
+        Message::ScaleRootSelected(root) => state_data.scale.selected.root = root,
+        Message::SelectionCleared => state_data.selection.clear(),
+        Message::StringsFlipped(value) => state_data.flip_strings = value,
+        Message::TensionShown(value) => state_data.show_tension = value,
+        Message::ThemeSelected(theme_name) => state_data.theme_name = theme_name,
+        Message::TuningSelected(tuning) => {
+            state_data.selection.clear();
+            state_data.tuning.capo_strings_input = format_capo_strings(tuning.capo);
+            state_data.tuning.selected = Some(tuning);
+        }
+        Message::UnhighlightedHidden(value) => state_data.hide_unhighlighted = value,
+        Message::WarningsDismissed => state_data.warnings.clear(),
+    }
+    iced::Task::none()
+}
+
+fn update_editor(data: &mut StateData, args: &ConfigArgs, message: EditorMessage) {
+    match message {
+        EditorMessage::Closed => {
+            data.editor = None;
+            return;
+        }
+        EditorMessage::Opened => {
+            match ConfigDocument::read(args) {
+                Ok(document) => {
+                    let selected_name = data.tuning.selected.as_ref().map(|x| x.name.as_str());
+                    data.editor = Some(StateEditor::new(document, selected_name));
+                }
+                Err(err) => {
+                    log::error!("Could not open tunings: {}", err);
+                    data.export.status = Some(format!("Could not open tunings: {}", err));
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+    let Some(editor) = &mut data.editor else {
+        return;
+    };
+    match message {
+        EditorMessage::CapoChanged(value) => {
+            editor.capo_input = value;
+            editor.update_capo();
+        }
+        EditorMessage::CapoStringsChanged(value) => {
+            editor.capo_strings_input = value;
+            editor.update_capo();
+        }
+        // Handled before the editor check.
+        EditorMessage::Closed | EditorMessage::Opened => {}
+        EditorMessage::FretsChanged(value) => {
+            // The capo and short strings are checked against the frets when the tunings are saved.
+            if let Ok(frets) = value.trim().parse::<u8>()
+                && frets > 0
+                && let Some(tuning) = editor.get_selected_mut()
+            {
+                tuning.total_frets = frets;
+            }
+            editor.frets_input = value;
+        }
+        EditorMessage::GaugesChanged(value) => {
+            if let Ok(gauges) = value
+                .split([' ', ','])
+                .filter(|x| !x.is_empty())
+                .map(str::parse::<StringGauge>)
+                .collect::<Result<Vec<_>, _>>()
+                && let Some(tuning) = editor.get_selected_mut()
+            {
+                tuning.gauges = gauges;
+            }
+            editor.gauges_input = value;
+        }
+        EditorMessage::Saved => {
+            if editor.get_selected().is_some() && !editor.frets_input.trim().parse::<u8>().is_ok_and(|x| x > 0) {
+                editor.status = Some(format!(
+                    "Not saved: invalid frets: {}, expected a number from 1 to {}",
+                    editor.frets_input,
+                    u8::MAX
+                ));
+                return;
+            }
+            if editor.get_selected().is_some() && !editor.update_capo() {
+                editor.status = Some(String::from(
+                    "Not saved: invalid capo, expected a fret number and strings such as 3-5",
+                ));
+                return;
+            }
+            let result = editor
+                .document
+                .write(&editor.tunings)
+                .and_then(|()| ConfigDocument::read(args));
+            editor.status = Some(match result {
+                Ok(document) => {
+                    let status = format!("Saved to {}", document.get_path().display());
+                    editor.tunings = document.get_tunings();
+                    editor.document = document;
+                    status
+                }
+                Err(ConfigError::FileChanged) => {
+                    log::error!("Could not save tunings: the file has been changed outside of the editor");
+                    String::from("Not saved: the file has been changed outside of the editor, reopen the editor")
+                }
+                Err(err) => {
+                    log::error!("Could not save tunings: {}", err);
+                    format!("Save failed: {}", err)
+                }
+            });
+        }
+        EditorMessage::ScaleLengthChanged(value) => {
+            let lengths = value
+                .split([' ', ','])
+                .filter(|x| !x.is_empty())
+                .map(str::parse::<f32>)
+                .collect::<Result<Vec<_>, _>>();
+            let scale_length = match lengths.as_deref() {
+                Ok([]) => Some(None),
+                Ok([x]) if *x > 0.0 => Some(Some((*x, *x))),
+                Ok([bass, treble]) if *bass > 0.0 && *treble > 0.0 => Some(Some((*bass, *treble))),
+                _ => None,
+            };
+            if let Some(scale_length) = scale_length
+                && let Some(tuning) = editor.get_selected_mut()
+            {
+                let neutral_fret = tuning.scale_length.map(|x| x.neutral_fret).unwrap_or_default();
+                tuning.scale_length = scale_length.map(|(bass, treble)| ScaleLength {
+                    bass,
+                    treble,
+                    neutral_fret,
+                });
+            }
+            editor.scale_length_input = value;
+        }
+        EditorMessage::StringAdded => {
+            if let Some(tuning) = editor.get_selected_mut() {
+                let pitch = match tuning.pitches.last() {
+                    Some(pitch) => pitch.transpose(5).unwrap_or(*pitch),
+                    None => Pitch::new(Note::E, 2),
+                };
+                tuning.pitches.push(pitch);
+                // Gauges are kept for every string or for none, a fourth higher is about three quarters as thick.
+                if let Some(gauge) = tuning.gauges.last() {
+                    tuning
+                        .gauges
+                        .push(StringGauge::new((gauge.value as f32 * 0.75).round() as u16));
+                }
+            }
+            editor.reset_gauges_input();
+        }
+        EditorMessage::StringRemoved(idx) => {
+            if let Some(tuning) = editor.get_selected_mut()
+                && idx < tuning.pitches.len()
+            {
+                tuning.pitches.remove(idx);
+                if idx < tuning.string_frets.len() {
+                    tuning.string_frets.remove(idx);
+                }
+                if idx < tuning.gauges.len() {
+                    tuning.gauges.remove(idx);
+                }
+            }
+            editor.reset_gauges_input();
+        }
+        EditorMessage::StringTransposed(idx, semitones) => {
+            if let Some(tuning) = editor.get_selected_mut()
+                && let Some(pitch) = tuning.pitches.get_mut(idx)
+            {
+                *pitch = pitch.transpose(semitones).unwrap_or(*pitch);
+            }
+        }
+        EditorMessage::TuningAdded => {
+            let tuning = Tuning {
+                name: String::from("New tuning"),
+                ..Tuning::default()
+            };
+            editor.tunings.push(DocumentTuning::new(tuning));
+            editor.select(Some(editor.tunings.len() - 1));
+        }
+        EditorMessage::TuningDeleted => {
+            if let Some(idx) = editor.selected {
+                editor.tunings.remove(idx);
+                editor.select(Some(idx.min(editor.tunings.len().saturating_sub(1))));
+            }
+        }
+        EditorMessage::TuningDuplicated => {
+            if let Some(idx) = editor.selected {
+                let mut tuning = editor.tunings[idx].get().clone();
+                tuning.name = format!("{} (copy)", tuning.name);
+                editor.tunings.insert(idx + 1, DocumentTuning::new(tuning));
+                editor.select(Some(idx + 1));
+            }
+        }
+        EditorMessage::TuningMovedDown => {
+            if let Some(idx) = editor.selected
+                && idx + 1 < editor.tunings.len()
+            {
+                editor.tunings.swap(idx, idx + 1);
+                editor.selected = Some(idx + 1);
+            }
+        }
+        EditorMessage::TuningMovedUp => {
+            if let Some(idx) = editor.selected
+                && idx > 0
+            {
+                editor.tunings.swap(idx, idx - 1);
+                editor.selected = Some(idx - 1);
+            }
+        }
+        EditorMessage::TuningRenamed(value) => {
+            if let Some(tuning) = editor.get_selected_mut() {
+                tuning.name = value;
+            }
+        }
+        EditorMessage::TuningSelected(idx) => editor.select(Some(idx)),
     }
 }
 
 fn view(state: &State) -> iced::Element<'_, Message> {
-    match state {
-        State::Running(data) => view_running(data),
-        State::ConfigError(err) => view_config_error(err),
+    match (&state.config_error, &state.data) {
+        (Some(err), data) => view_config_error(&state.config, err, data.is_some()),
+        (None, Some(data)) => view_running(data),
+        (None, None) => iced::widget::text!("No configuration").into(),
     }
 }
 
 fn view_running(data: &StateData) -> iced::Element<'_, Message> {
-    let tuning_selected = &data.tuning.selected;
-    let note_format_selected = Some(data.note_format);
-    let fretboard: iced::Element<Message> = match tuning_selected {
-        Some(tuning) => Fretboard::new(tuning.clone(), data.note_format, data.theme_name).into(),
+    let fretboard: iced::Element<Message> = match create_fretboard(data) {
+        Some(fretboard) => fretboard.on_toggle(Message::PositionToggled).into(),
         None => iced::widget::text!("Select tuning").into(),
     };
+    let controls = match &data.editor {
+        Some(editor) => view_editor(editor, data.note_format),
+        None => view_controls(data),
+    };
     iced::widget::container(
         iced::widget::column![
-            iced::widget::container(fretboard).width(iced::Length::FillPortion(3)),
+            view_warnings(&data.warnings),
             iced::widget::row![
-                iced::widget::container(iced::widget::combo_box(
-                    &data.tuning.combo_box,
-                    "Tuning",
-                    tuning_selected.as_ref(),
-                    Message::TuningSelected
-                ))
+                iced::widget::container(fretboard).width(iced::Length::FillPortion(3)),
+                view_tension(data),
+            ]
+            .spacing(DEFAULT_PADDING),
+            controls,
+        ]
+        .spacing(DEFAULT_PADDING),
+    )
+    .padding(iced::padding::all(DEFAULT_PADDING))
+    .into()
+}
+
+fn view_controls(data: &StateData) -> iced::Element<'_, Message> {
+    let tuning_selected = &data.tuning.selected;
+    let note_format_selected = Some(data.note_format);
+    let label_mode_selected = Some(data.label_mode);
+    let scale_selected = data.scale.selected;
+    iced::widget::column![
+        view_chord_candidates(data),
+        iced::widget::row![
+            iced::widget::pick_list(
+                InstrumentChoice::list(&data.tuning.items),
+                Some(data.tuning.instrument.clone()),
+                Message::InstrumentSelected
+            ),
+            iced::widget::container(iced::widget::combo_box(
+                &data.tuning.combo_box,
+                "Tuning",
+                tuning_selected.as_ref(),
+                Message::TuningSelected
+            ))
+            .width(iced::Length::FillPortion(3)),
+            iced::widget::radio(
+                "Flat",
+                NoteFormat::Flat,
+                note_format_selected,
+                Message::NoteFormatSelected
+            ),
+            iced::widget::radio(
+                "Sharp",
+                NoteFormat::Sharp,
+                note_format_selected,
+                Message::NoteFormatSelected
+            ),
+            iced::widget::radio("Note", LabelMode::Note, label_mode_selected, Message::LabelModeSelected),
+            iced::widget::radio(
+                "Interval",
+                LabelMode::Interval,
+                label_mode_selected,
+                Message::LabelModeSelected
+            ),
+            iced::widget::radio(
+                "Degree",
+                LabelMode::Degree,
+                label_mode_selected,
+                Message::LabelModeSelected
+            ),
+            iced::widget::pick_list(
+                NoteChoice::list(data.note_format),
+                Some(NoteChoice {
+                    note: scale_selected.root,
+                    note_format: data.note_format,
+                }),
+                |x| Message::ScaleRootSelected(x.note)
+            ),
+            iced::widget::pick_list(ScaleKind::ALL, Some(scale_selected.kind), Message::ScaleKindSelected),
+            iced::widget::checkbox(data.hide_unhighlighted)
+                .label("Hide other notes")
+                .on_toggle(Message::UnhighlightedHidden),
+        ]
+        .spacing(DEFAULT_PADDING)
+        .align_y(iced::alignment::Vertical::Center),
+        iced::widget::row![
+            iced::widget::text_input("Chord (e.g. Cmaj7, F#m7b5, G7#9)", &data.chord.input)
+                .on_input(Message::ChordInputChanged)
                 .width(iced::Length::FillPortion(3)),
-                iced::widget::radio(
-                    "Flat",
-                    NoteFormat::Flat,
-                    note_format_selected,
-                    Message::NoteFormatSelected
-                ),
-                iced::widget::radio(
-                    "Sharp",
-                    NoteFormat::Sharp,
-                    note_format_selected,
-                    Message::NoteFormatSelected
-                )
+            iced::widget::pick_list(
+                ChordQuality::ALL,
+                data.chord.selected.as_ref().map(|x| x.quality),
+                Message::ChordQualitySelected
+            )
+            .placeholder("Chord quality"),
+            iced::widget::button("Clear chord").on_press(Message::ChordCleared),
+            iced::widget::button("Clear selection").on_press(Message::SelectionCleared),
+            iced::widget::pick_list(
+                tuning_selected
+                    .as_ref()
+                    .map(|x| CapoChoice::list(x.total_frets))
+                    .unwrap_or_default(),
+                tuning_selected
+                    .as_ref()
+                    .map(|x| CapoChoice(x.capo.map(|x| x.fret).unwrap_or_default())),
+                Message::CapoFretSelected
+            ),
+            iced::widget::text_input("Capo strings (e.g. 3-5)", &data.tuning.capo_strings_input)
+                .on_input(Message::CapoStringsChanged)
+                .width(iced::Length::FillPortion(1)),
+        ]
+        .spacing(DEFAULT_PADDING)
+        .align_y(iced::alignment::Vertical::Center),
+        iced::widget::row![
+            iced::widget::pick_list(ThemeName::ALL, Some(data.theme_name), Message::ThemeSelected),
+            iced::widget::pick_list(Orientation::ALL, Some(data.orientation), Message::OrientationSelected),
+            iced::widget::checkbox(data.left_handed)
+                .label("Left-handed")
+                .on_toggle(Message::LeftHandedToggled),
+            iced::widget::checkbox(data.flip_strings)
+                .label("Flip strings")
+                .on_toggle(Message::StringsFlipped),
+            iced::widget::checkbox(data.show_tension)
+                .label("Tension")
+                .on_toggle(Message::TensionShown),
+            iced::widget::text_input("Export path", &data.export.path)
+                .on_input(Message::ExportPathChanged)
+                .width(iced::Length::FillPortion(1)),
+            iced::widget::button("Export SVG").on_press(Message::Exported),
+            iced::widget::button("Edit tunings").on_press(Message::Editor(EditorMessage::Opened)),
+            iced::widget::text(data.export.status.as_deref().unwrap_or_default()),
+        ]
+        .spacing(DEFAULT_PADDING)
+        .align_y(iced::alignment::Vertical::Center),
+    ]
+    .spacing(DEFAULT_PADDING)
+    .into()
+}
+
+fn view_editor(editor: &StateEditor, note_format: NoteFormat) -> iced::Element<'_, Message> {
+    let tunings = iced::widget::column(editor.tunings.iter().enumerate().map(|(idx, x)| {
+        iced::widget::button(iced::widget::text(x.get().name.as_str()))
+            .on_press(Message::Editor(EditorMessage::TuningSelected(idx)))
+            .style(if editor.selected == Some(idx) {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            })
+            .width(iced::Length::Fill)
+            .into()
+    }))
+    .spacing(DEFAULT_PADDING);
+    let selected = editor.selected;
+    let tuning_actions = iced::widget::row![
+        iced::widget::button("New").on_press(Message::Editor(EditorMessage::TuningAdded)),
+        iced::widget::button("Duplicate")
+            .on_press_maybe(selected.map(|_| Message::Editor(EditorMessage::TuningDuplicated))),
+        iced::widget::button("Move up").on_press_maybe(
+            selected
+                .filter(|x| *x > 0)
+                .map(|_| Message::Editor(EditorMessage::TuningMovedUp))
+        ),
+        iced::widget::button("Move down").on_press_maybe(
+            selected
+                .filter(|x| x + 1 < editor.tunings.len())
+                .map(|_| Message::Editor(EditorMessage::TuningMovedDown))
+        ),
+        iced::widget::button("Delete")
+            .on_press_maybe(selected.map(|_| Message::Editor(EditorMessage::TuningDeleted)))
+            .style(iced::widget::button::danger),
+    ]
+    .spacing(DEFAULT_PADDING);
+    let details: iced::Element<Message> = match editor.get_selected() {
+        Some(tuning) => {
+            let strings_count = tuning.pitches.len();
+            let strings = iced::widget::column(tuning.pitches.iter().enumerate().rev().map(|(idx, pitch)| {
+                iced::widget::row![
+                    iced::widget::text!("String {}", strings_count - idx).width(iced::Length::Fixed(80.0)),
+                    iced::widget::text!("{}{}", pitch.note.format(note_format), pitch.octave)
+                        .font(iced::Font::MONOSPACE)
+                        .width(iced::Length::Fixed(40.0)),
+                    iced::widget::button("-").on_press(Message::Editor(EditorMessage::StringTransposed(idx, -1))),
+                    iced::widget::button("+").on_press(Message::Editor(EditorMessage::StringTransposed(idx, 1))),
+                    iced::widget::button("Remove").on_press(Message::Editor(EditorMessage::StringRemoved(idx))),
+                ]
+                .spacing(DEFAULT_PADDING)
+                .align_y(iced::alignment::Vertical::Center)
+                .into()
+            }))
+            .spacing(DEFAULT_PADDING);
+            iced::widget::column![
+                iced::widget::row![
+                    iced::widget::text_input("Name", &tuning.name)
+                        .on_input(|x| Message::Editor(EditorMessage::TuningRenamed(x)))
+                        .width(iced::Length::FillPortion(3)),
+                    iced::widget::text_input("Frets", &editor.frets_input)
+                        .on_input(|x| Message::Editor(EditorMessage::FretsChanged(x)))
+                        .width(iced::Length::FillPortion(1)),
+                    iced::widget::button("Add string").on_press(Message::Editor(EditorMessage::StringAdded)),
+                ]
+                .spacing(DEFAULT_PADDING),
+                iced::widget::row![
+                    iced::widget::text_input(
+                        "Gauges from the bass side, e.g. 46 36 26 17 13 10",
+                        &editor.gauges_input
+                    )
+                    .on_input(|x| Message::Editor(EditorMessage::GaugesChanged(x)))
+                    .width(iced::Length::FillPortion(3)),
+                    iced::widget::text_input("Scale length", &editor.scale_length_input)
+                        .on_input(|x| Message::Editor(EditorMessage::ScaleLengthChanged(x)))
+                        .width(iced::Length::FillPortion(1)),
+                ]
+                .spacing(DEFAULT_PADDING),
+                iced::widget::row![
+                    iced::widget::text_input("Capo fret", &editor.capo_input)
+                        .on_input(|x| Message::Editor(EditorMessage::CapoChanged(x)))
+                        .width(iced::Length::FillPortion(1)),
+                    iced::widget::text_input("Capo strings (e.g. 3-5)", &editor.capo_strings_input)
+                        .on_input(|x| Message::Editor(EditorMessage::CapoStringsChanged(x)))
+                        .width(iced::Length::FillPortion(1)),
+                ]
+                .spacing(DEFAULT_PADDING),
+                iced::widget::scrollable(strings).height(iced::Length::Fill),
+            ]
+            .spacing(DEFAULT_PADDING)
+            .into()
+        }
+        None => iced::widget::text("Select a tuning to edit").into(),
+    };
+    iced::widget::column![
+        iced::widget::row![
+            iced::widget::column![
+                iced::widget::scrollable(tunings).height(iced::Length::Fill),
+                tuning_actions,
+            ]
+            .spacing(DEFAULT_PADDING)
+            .width(iced::Length::FillPortion(1)),
+            iced::widget::container(details).width(iced::Length::FillPortion(1)),
+        ]
+        .spacing(DEFAULT_PADDING)
+        .height(iced::Length::Fixed(EDITOR_HEIGHT)),
+        iced::widget::row![
+            iced::widget::button("Save").on_press(Message::Editor(EditorMessage::Saved)),
+            iced::widget::button("Close").on_press(Message::Editor(EditorMessage::Closed)),
+            iced::widget::text(editor.status.as_deref().unwrap_or_default()),
+        ]
+        .spacing(DEFAULT_PADDING)
+        .align_y(iced::alignment::Vertical::Center),
+    ]
+    .spacing(DEFAULT_PADDING)
+    .into()
+}
+
+fn get_displayed_tuning(data: &StateData) -> Option<&Tuning> {
+    match &data.editor {
+        Some(editor) => editor.get_selected(),
+        None => data.tuning.selected.as_ref(),
+    }
+}
+
+fn create_fretboard<'a>(data: &StateData) -> Option<Fretboard<'a, Message>> {
+    let tuning = get_displayed_tuning(data)?;
+    if tuning.pitches.is_empty() {
+        return None;
+    }
+    let mut fretboard = Fretboard::new(tuning.clone(), data.note_format, data.theme_name)
+        .with_scale(data.scale.selected)
+        .with_label_mode(data.label_mode)
+        .with_hidden_unhighlighted(data.hide_unhighlighted)
+        .with_orientation(data.orientation)
+        .with_left_handed(data.left_handed)
+        .with_flipped_strings(data.flip_strings)
+        .with_selected(data.selection.clone());
+    if let Some(chord) = &data.chord.selected {
+        fretboard = fretboard.with_chord(chord.clone());
+    }
+    Some(fretboard)
+}
+
+fn view_chord_candidates(data: &StateData) -> iced::Element<'_, Message> {
+    const MAX_CANDIDATES: usize = 5;
+    let pitches: Vec<_> = match &data.tuning.selected {
+        Some(tuning) => data.selection.iter().filter_map(|x| tuning.get_pitch(*x)).collect(),
+        None => Vec::new(),
+    };
+    let candidates = ChordCandidate::identify(&pitches);
+    let content: iced::Element<Message> = if candidates.is_empty() {
+        iced::widget::text(if pitches.len() < 2 {
+            "Select positions on the fretboard to identify a chord"
+        } else {
+            "Unknown chord"
+        })
+        .into()
+    } else {
+        iced::widget::row(candidates.iter().take(MAX_CANDIDATES).enumerate().map(|(idx, x)| {
+            let text = iced::widget::text(x.format(data.note_format));
+            if idx == 0 {
+                text.size(DEFAULT_PADDING * 2.0).into()
+            } else {
+                text.into()
+            }
+        }))
+        .spacing(DEFAULT_PADDING * 2.0)
+        .align_y(iced::alignment::Vertical::Center)
+        .into()
+    };
+    iced::widget::container(content).into()
+}
+
+fn view_tension(data: &StateData) -> Option<iced::Element<'_, Message>> {
+    if !data.show_tension {
+        return None;
+    }
+    let content: iced::Element<Message> =
+        match get_displayed_tuning(data).map(|tuning| StringTension::calculate(tuning, &data.unit_weights)) {
+            Some(Ok(tensions)) => {
+                let strings_count = tensions.len();
+                let pounds: f32 = tensions.iter().map(|x| x.pounds).sum();
+                let kilograms: f32 = tensions.iter().map(StringTension::get_kilograms).sum();
+                let rows = tensions.iter().rev().map(|x| {
+                    let pitch = format!("{}{}", x.pitch.note.format(data.note_format), x.pitch.octave);
+                    iced::widget::text(format!(
+                        "{:>2} {:<4} {:>4} {:>6.1} lbs {:>5.1} kg",
+                        strings_count - x.string_idx,
+                        pitch,
+                        x.gauge.to_string(),
+                        x.pounds,
+                        x.get_kilograms()
+                    ))
+                    .font(iced::Font::MONOSPACE)
+                    .into()
+                });
+                iced::widget::column![
+                    iced::widget::column(rows),
+                    iced::widget::text(format!("Total {:>10.1} lbs {:>5.1} kg", pounds, kilograms))
+                        .font(iced::Font::MONOSPACE),
+                ]
+                .spacing(DEFAULT_PADDING)
+                .into()
+            }
+            Some(Err(err)) => iced::widget::text(format!(
+                "Tension is not available: {}, set scale-length and gauges of the tuning",
+                err
+            ))
+            .into(),
+            None => iced::widget::text("Select tuning").into(),
+        };
+    Some(
+        iced::widget::container(
+            iced::widget::column![iced::widget::text("String tension"), content].spacing(DEFAULT_PADDING),
+        )
+        .padding(iced::padding::all(DEFAULT_PADDING))
+        .width(TENSION_WIDTH)
+        .height(iced::Length::Fill)
+        .style(iced::widget::container::bordered_box)
+        .into(),
+    )
+}
+
+fn view_warnings(warnings: &[ConfigIssue]) -> Option<iced::Element<'_, Message>> {
+    if warnings.is_empty() {
+        return None;
+    }
+    let messages = iced::widget::column(warnings.iter().map(|x| iced::widget::text(x.to_string()).into()));
+    Some(
+        iced::widget::container(
+            iced::widget::row![
+                iced::widget::container(messages).width(iced::Length::Fill),
+                iced::widget::button("Dismiss").on_press(Message::WarningsDismissed),
             ]
             .spacing(DEFAULT_PADDING)
             .align_y(iced::alignment::Vertical::Center),
+        )
+        .padding(iced::padding::all(DEFAULT_PADDING))
+        .width(iced::Length::Fill)
+        .style(iced::widget::container::warning)
+        .into(),
+    )
+}
+
+fn view_config_error<'a>(config: &'a StateConfig, err: &'a ConfigError, has_data: bool) -> iced::Element<'a, Message> {
+    let path = match &config.path {
+        Some(path) => format!("File: {}", path.display()),
+        None => String::from("Configuration file path is not found"),
+    };
+    iced::widget::container(
+        iced::widget::column![
+            iced::widget::text("Configuration error").size(DEFAULT_PADDING * 2.0),
+            iced::widget::text(path),
+            iced::widget::container(
+                iced::widget::scrollable(
+                    iced::widget::text(err.format_report().unwrap_or_else(|| err.to_string()))
+                        .font(iced::Font::MONOSPACE)
+                        .wrapping(iced::widget::text::Wrapping::None)
+                )
+                .direction(iced::widget::scrollable::Direction::Both {
+                    vertical: iced::widget::scrollable::Scrollbar::default(),
+                    horizontal: iced::widget::scrollable::Scrollbar::default(),
+                })
+                .width(iced::Length::Fill)
+                .height(iced::Length::Fill)
+            )
+            .padding(iced::padding::all(DEFAULT_PADDING))
+            .style(iced::widget::container::bordered_box),
+            iced::widget::row![
+                iced::widget::button("Reload config").on_press(Message::ConfigReloaded),
+                iced::widget::button(match has_data {
+                    true => "Continue with the last configuration",
+                    false => "Continue with defaults",
+                })
+                .on_press(Message::ConfigDefaultsUsed),
+            ]
+            .spacing(DEFAULT_PADDING),
         ]
         .spacing(DEFAULT_PADDING),
     )
     .padding(iced::padding::all(DEFAULT_PADDING))
     .into()
 }
-
-fn view_config_error(err: &ConfigError) -> iced::Element<'_, Message> {
-    let message = err.to_string();
-    iced::widget::text(message).into()
-}
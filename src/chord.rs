@@ -0,0 +1,741 @@
+use std::{error, fmt, str};
+
+use crate::{
+    interval::Interval,
+    theme::Palette,
+    tuning::{Note, NoteFormat, Pitch},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chord {
+    pub root: Note,
+    pub quality: ChordQuality,
+    pub alterations: Vec<ChordAlteration>,
+    pub bass: Option<Note>,
+}
+
+impl Chord {
+    pub fn new(root: Note, quality: ChordQuality) -> Self {
+        Self {
+            root,
+            quality,
+            alterations: Vec::new(),
+            bass: None,
+        }
+    }
+
+    pub fn tones(&self) -> Vec<ChordTone> {
+        let mut result: Vec<ChordTone> = self
+            .quality
+            .tones()
+            .iter()
+            .map(|(semitones, function)| ChordTone::new(*semitones, *function))
+            .collect();
+        for alteration in &self.alterations {
+            let replaces = alteration.replaces();
+            result.retain(|x| !replaces.contains(&x.interval.semitones()));
+            if let Some((semitones, function)) = alteration.tone()
+                && result.iter().all(|x| x.interval.semitones() != semitones)
+            {
+                result.push(ChordTone::new(semitones, function));
+            }
+        }
+        result
+    }
+
+    pub fn get_function(&self, note: Note) -> Option<ChordFunction> {
+        let interval = Interval::between(self.root, note);
+        self.tones()
+            .into_iter()
+            .find(|x| x.interval == interval)
+            .map(|x| x.function)
+    }
+
+    pub fn format(&self, note_format: NoteFormat) -> String {
+        let mut result = format!("{}{}", self.root.format(note_format), self.quality.suffix());
+        for alteration in &self.alterations {
+            result.push_str(alteration.suffix());
+        }
+        if let Some(bass) = self.bass {
+            result.push('/');
+            result.push_str(bass.format(note_format));
+        }
+        result
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "{}", self.format(NoteFormat::Sharp))
+    }
+}
+
+impl str::FromStr for Chord {
+    type Err = ChordError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let err = || ChordError::Parse(String::from(value));
+        // A slash is also a part of 6/9 chords, only a note after it is the bass.
+        let (symbol, bass) = match value.trim().rsplit_once('/') {
+            Some((symbol, bass)) if parse_note(bass).is_some() => (symbol, parse_note(bass)),
+            _ => (value.trim(), None),
+        };
+        let (root, rest) = split_note(symbol).ok_or_else(err)?;
+        let (quality, mut rest) = ChordQuality::ALL
+            .iter()
+            .flat_map(|quality| quality.aliases().iter().map(move |alias| (*quality, *alias)))
+            .filter(|(_, alias)| rest.starts_with(alias))
+            .max_by_key(|(_, alias)| alias.len())
+            .map(|(quality, alias)| (quality, &rest[alias.len()..]))
+            .ok_or_else(err)?;
+        let mut alterations = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(['(', ')', ',', ' ']);
+            if rest.is_empty() {
+                break;
+            }
+            let (alteration, alias) = ChordAlteration::ALL
+                .iter()
+                .flat_map(|alteration| alteration.aliases().iter().map(move |alias| (*alteration, *alias)))
+                .filter(|(_, alias)| rest.starts_with(alias))
+                .max_by_key(|(_, alias)| alias.len())
+                .ok_or_else(err)?;
+            alterations.push(alteration);
+            rest = &rest[alias.len()..];
+        }
+        Ok(Self {
+            root,
+            quality,
+            alterations,
+            bass,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChordCandidate {
+    pub chord: Chord,
+    pub omitted: Vec<Interval>,
+    score: i32,
+}
+
+impl ChordCandidate {
+    const MAX_ALTERATIONS: usize = 2;
+    const SCORE_ALTERATION: i32 = 8;
+    const SCORE_BASE: i32 = 100;
+    const SCORE_INVERSION: i32 = 5;
+    const SCORE_OMITTED: i32 = 10;
+
+    /// The lowest pitch is treated as the bass note.
+    pub fn identify(pitches: &[Pitch]) -> Vec<Self> {
+        let Some(bass) = pitches.iter().min_by_key(|x| x.get_midi_number()).map(|x| x.note) else {
+            return Vec::new();
+        };
+        let mut notes: Vec<Note> = Vec::new();
+        for pitch in pitches {
+            if !notes.contains(&pitch.note) {
+                notes.push(pitch.note);
+            }
+        }
+        if notes.len() < 2 {
+            return Vec::new();
+        }
+        let mut result: Vec<Self> = notes
+            .iter()
+            .flat_map(|root| {
+                let notes = &notes;
+                ChordQuality::ALL
+                    .iter()
+                    .filter_map(move |quality| Self::try_match(*root, *quality, notes, bass))
+            })
+            .collect();
+        result.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.chord.to_string().cmp(&b.chord.to_string()))
+        });
+        let mut seen: Vec<(Note, Vec<Interval>)> = Vec::new();
+        result.retain(|x| {
+            let mut intervals: Vec<Interval> = x.chord.tones().into_iter().map(|x| x.interval).collect();
+            intervals.sort();
+            let key = (x.chord.root, intervals);
+            if seen.contains(&key) {
+                false
+            } else {
+                seen.push(key);
+                true
+            }
+        });
+        result
+    }
+
+    fn try_match(root: Note, quality: ChordQuality, notes: &[Note], bass: Note) -> Option<Self> {
+        let present: Vec<Interval> = notes.iter().map(|x| Interval::between(root, *x)).collect();
+        let mut chord = Chord::new(root, quality);
+        for interval in &present {
+            if quality.tones().iter().any(|(x, _)| *x == interval.semitones()) {
+                continue;
+            }
+            let alteration = ChordAlteration::ALL.into_iter().find(|x| {
+                x.tone().is_some_and(|(semitones, _)| semitones == interval.semitones())
+                    && x.replaces()
+                        .iter()
+                        .all(|replaced| present.iter().all(|x| x.semitones() != *replaced))
+            })?;
+            // Only one altered fifth, both altered ninths are common in dominant chords.
+            if alteration.is_fifth() && chord.alterations.iter().any(|x| x.is_fifth()) {
+                return None;
+            }
+            chord.alterations.push(alteration);
+        }
+        if chord.alterations.len() > Self::MAX_ALTERATIONS
+            || (!chord.alterations.is_empty() && matches!(quality, ChordQuality::Power))
+        {
+            return None;
+        }
+        let tones = chord.tones();
+        if tones.iter().any(|x| x.function == ChordFunction::Third)
+            && !present.iter().any(|x| {
+                tones
+                    .iter()
+                    .any(|tone| tone.interval == *x && tone.function == ChordFunction::Third)
+            })
+        {
+            return None;
+        }
+        let omitted: Vec<Interval> = tones
+            .iter()
+            .filter(|tone| !present.contains(&tone.interval))
+            .map(|tone| tone.interval)
+            .collect();
+        if omitted
+            .iter()
+            .any(|x| !quality.get_omittable().contains(&x.semitones()))
+        {
+            return None;
+        }
+        if bass != root {
+            chord.bass = Some(bass);
+        }
+        let score = Self::SCORE_BASE
+            - Self::SCORE_OMITTED * omitted.len() as i32
+            - Self::SCORE_ALTERATION * chord.alterations.len() as i32
+            - if bass != root { Self::SCORE_INVERSION } else { 0 }
+            - tones.len() as i32;
+        Some(Self { chord, omitted, score })
+    }
+
+    pub fn format(&self, note_format: NoteFormat) -> String {
+        let mut result = self.chord.format(note_format);
+        if !self.omitted.is_empty() {
+            let omitted: Vec<String> = self
+                .omitted
+                .iter()
+                .map(|x| format!("no {}", x.get_extension_degree()))
+                .collect();
+            result.push_str(&format!(" ({})", omitted.join(", ")));
+        }
+        result
+    }
+}
+
+fn split_note(value: &str) -> Option<(Note, &str)> {
+    let mut chars = value.chars();
+    let natural = match chars.next()? {
+        'A' => Note::A,
+        'B' => Note::B,
+        'C' => Note::C,
+        'D' => Note::D,
+        'E' => Note::E,
+        'F' => Note::F,
+        'G' => Note::G,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    Some(if let Some(rest) = rest.strip_prefix(['#', '♯']) {
+        (natural.transpose(Interval::new(1)), rest)
+    } else if let Some(rest) = rest.strip_prefix(['b', '♭']) {
+        (natural.transpose(Interval::new(11)), rest)
+    } else {
+        (natural, rest)
+    })
+}
+
+fn parse_note(value: &str) -> Option<Note> {
+    match split_note(value.trim()) {
+        Some((note, "")) => Some(note),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ChordTone {
+    pub interval: Interval,
+    pub function: ChordFunction,
+}
+
+impl ChordTone {
+    fn new(semitones: u8, function: ChordFunction) -> Self {
+        Self {
+            interval: Interval::new(semitones),
+            function,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChordFunction {
+    Root,
+    Third,
+    Fifth,
+    Seventh,
+    Extension,
+}
+
+impl ChordFunction {
+    pub fn get_color(self, palette: Palette) -> iced::Color {
+        match self {
+            Self::Root => palette.red,
+            Self::Third => palette.yellow,
+            Self::Fifth => palette.green,
+            Self::Seventh => palette.blue,
+            Self::Extension => palette.mauve,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Suspended2,
+    Suspended4,
+    Power,
+    Major6,
+    Minor6,
+    Major6Add9,
+    Minor6Add9,
+    Dominant7,
+    Major7,
+    Minor7,
+    MinorMajor7,
+    HalfDiminished7,
+    Diminished7,
+    Augmented7,
+    Dominant7Suspended4,
+    Dominant9,
+    Major9,
+    Minor9,
+    Dominant11,
+    Minor11,
+    Dominant13,
+    Major13,
+    Minor13,
+}
+
+impl ChordQuality {
+    pub const ALL: [ChordQuality; 27] = [
+        Self::Major,
+        Self::Minor,
+        Self::Diminished,
+        Self::Augmented,
+        Self::Suspended2,
+        Self::Suspended4,
+        Self::Power,
+        Self::Major6,
+        Self::Minor6,
+        Self::Major6Add9,
+        Self::Minor6Add9,
+        Self::Dominant7,
+        Self::Major7,
+        Self::Minor7,
+        Self::MinorMajor7,
+        Self::HalfDiminished7,
+        Self::Diminished7,
+        Self::Augmented7,
+        Self::Dominant7Suspended4,
+        Self::Dominant9,
+        Self::Major9,
+        Self::Minor9,
+        Self::Dominant11,
+        Self::Minor11,
+        Self::Dominant13,
+        Self::Major13,
+        Self::Minor13,
+    ];
+
+    pub fn tones(self) -> &'static [(u8, ChordFunction)] {
+        use ChordFunction::*;
+        match self {
+            Self::Major => &[(0, Root), (4, Third), (7, Fifth)],
+            Self::Minor => &[(0, Root), (3, Third), (7, Fifth)],
+            Self::Diminished => &[(0, Root), (3, Third), (6, Fifth)],
+            Self::Augmented => &[(0, Root), (4, Third), (8, Fifth)],
+            Self::Suspended2 => &[(0, Root), (2, Third), (7, Fifth)],
+            Self::Suspended4 => &[(0, Root), (5, Third), (7, Fifth)],
+            Self::Power => &[(0, Root), (7, Fifth)],
+            Self::Major6 => &[(0, Root), (4, Third), (7, Fifth), (9, Extension)],
+            Self::Minor6 => &[(0, Root), (3, Third), (7, Fifth), (9, Extension)],
+            Self::Major6Add9 => &[(0, Root), (4, Third), (7, Fifth), (9, Extension), (2, Extension)],
+            Self::Minor6Add9 => &[(0, Root), (3, Third), (7, Fifth), (9, Extension), (2, Extension)],
+            Self::Dominant7 => &[(0, Root), (4, Third), (7, Fifth), (10, Seventh)],
+            Self::Major7 => &[(0, Root), (4, Third), (7, Fifth), (11, Seventh)],
+            Self::Minor7 => &[(0, Root), (3, Third), (7, Fifth), (10, Seventh)],
+            Self::MinorMajor7 => &[(0, Root), (3, Third), (7, Fifth), (11, Seventh)],
+            Self::HalfDiminished7 => &[(0, Root), (3, Third), (6, Fifth), (10, Seventh)],
+            Self::Diminished7 => &[(0, Root), (3, Third), (6, Fifth), (9, Seventh)],
+            Self::Augmented7 => &[(0, Root), (4, Third), (8, Fifth), (10, Seventh)],
+            Self::Dominant7Suspended4 => &[(0, Root), (5, Third), (7, Fifth), (10, Seventh)],
+            Self::Dominant9 => &[(0, Root), (4, Third), (7, Fifth), (10, Seventh), (2, Extension)],
+            Self::Major9 => &[(0, Root), (4, Third), (7, Fifth), (11, Seventh), (2, Extension)],
+            Self::Minor9 => &[(0, Root), (3, Third), (7, Fifth), (10, Seventh), (2, Extension)],
+            Self::Dominant11 => &[
+                (0, Root),
+                (4, Third),
+                (7, Fifth),
+                (10, Seventh),
+                (2, Extension),
+                (5, Extension),
+            ],
+            Self::Minor11 => &[
+                (0, Root),
+                (3, Third),
+                (7, Fifth),
+                (10, Seventh),
+                (2, Extension),
+                (5, Extension),
+            ],
+            Self::Dominant13 => &[
+                (0, Root),
+                (4, Third),
+                (7, Fifth),
+                (10, Seventh),
+                (2, Extension),
+                (9, Extension),
+            ],
+            Self::Major13 => &[
+                (0, Root),
+                (4, Third),
+                (7, Fifth),
+                (11, Seventh),
+                (2, Extension),
+                (9, Extension),
+            ],
+            Self::Minor13 => &[
+                (0, Root),
+                (3, Third),
+                (7, Fifth),
+                (10, Seventh),
+                (2, Extension),
+                (5, Extension),
+                (9, Extension),
+            ],
+        }
+    }
+
+    pub fn suffix(self) -> &'static str {
+        self.aliases()[0]
+    }
+
+    fn get_omittable(self) -> &'static [u8] {
+        match self {
+            Self::Dominant11 | Self::Minor11 => &[2, 7],
+            Self::Dominant13 | Self::Major13 | Self::Minor13 => &[2, 5, 7],
+            _ => &[7],
+        }
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::Major => &["", "M", "maj"],
+            Self::Minor => &["m", "min", "-"],
+            Self::Diminished => &["dim", "°", "o"],
+            Self::Augmented => &["aug", "+"],
+            Self::Suspended2 => &["sus2"],
+            Self::Suspended4 => &["sus4", "sus"],
+            Self::Power => &["5"],
+            Self::Major6 => &["6", "M6", "maj6"],
+            Self::Minor6 => &["m6", "min6", "-6"],
+            Self::Major6Add9 => &["6/9", "69", "6add9"],
+            Self::Minor6Add9 => &["m6/9", "m69", "min6/9", "-6/9"],
+            Self::Dominant7 => &["7", "dom7"],
+            Self::Major7 => &["maj7", "M7", "Δ", "Δ7", "ma7"],
+            Self::Minor7 => &["m7", "min7", "-7"],
+            Self::MinorMajor7 => &["m(maj7)", "mM7", "mmaj7", "minmaj7", "-maj7", "m(M7)"],
+            Self::HalfDiminished7 => &["m7b5", "ø", "ø7", "-7b5", "min7b5"],
+            Self::Diminished7 => &["dim7", "°7", "o7"],
+            Self::Augmented7 => &["aug7", "+7", "7+"],
+            Self::Dominant7Suspended4 => &["7sus4", "7sus"],
+            Self::Dominant9 => &["9"],
+            Self::Major9 => &["maj9", "M9", "Δ9"],
+            Self::Minor9 => &["m9", "min9", "-9"],
+            Self::Dominant11 => &["11"],
+            Self::Minor11 => &["m11", "min11", "-11"],
+            Self::Dominant13 => &["13"],
+            Self::Major13 => &["maj13", "M13", "Δ13"],
+            Self::Minor13 => &["m13", "min13", "-13"],
+        }
+    }
+}
+
+impl fmt::Display for ChordQuality {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "{}",
+            match self {
+                Self::Major => "Major",
+                Self::Minor => "Minor",
+                Self::Diminished => "Diminished",
+                Self::Augmented => "Augmented",
+                Self::Suspended2 => "Suspended 2",
+                Self::Suspended4 => "Suspended 4",
+                Self::Power => "Power",
+                Self::Major6 => "Major 6",
+                Self::Minor6 => "Minor 6",
+                Self::Major6Add9 => "Major 6/9",
+                Self::Minor6Add9 => "Minor 6/9",
+                Self::Dominant7 => "Dominant 7",
+                Self::Major7 => "Major 7",
+                Self::Minor7 => "Minor 7",
+                Self::MinorMajor7 => "Minor Major 7",
+                Self::HalfDiminished7 => "Half-diminished 7",
+                Self::Diminished7 => "Diminished 7",
+                Self::Augmented7 => "Augmented 7",
+                Self::Dominant7Suspended4 => "Dominant 7 Suspended 4",
+                Self::Dominant9 => "Dominant 9",
+                Self::Major9 => "Major 9",
+                Self::Minor9 => "Minor 9",
+                Self::Dominant11 => "Dominant 11",
+                Self::Minor11 => "Minor 11",
+                Self::Dominant13 => "Dominant 13",
+                Self::Major13 => "Major 13",
+                Self::Minor13 => "Minor 13",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChordAlteration {
+    Flat5,
+    Sharp5,
+    Flat9,
+    Sharp9,
+    Sharp11,
+    Flat13,
+    Add9,
+    Add11,
+    Add13,
+    Suspended2,
+    Suspended4,
+    No5,
+}
+
+impl ChordAlteration {
+    const ALL: [ChordAlteration; 12] = [
+        Self::Flat5,
+        Self::Sharp5,
+        Self::Flat9,
+        Self::Sharp9,
+        Self::Sharp11,
+        Self::Flat13,
+        Self::Add9,
+        Self::Add11,
+        Self::Add13,
+        Self::Suspended2,
+        Self::Suspended4,
+        Self::No5,
+    ];
+
+    fn tone(self) -> Option<(u8, ChordFunction)> {
+        match self {
+            Self::Flat5 => Some((6, ChordFunction::Fifth)),
+            Self::Sharp5 => Some((8, ChordFunction::Fifth)),
+            Self::Flat9 => Some((1, ChordFunction::Extension)),
+            Self::Sharp9 => Some((3, ChordFunction::Extension)),
+            Self::Sharp11 => Some((6, ChordFunction::Extension)),
+            Self::Flat13 => Some((8, ChordFunction::Extension)),
+            Self::Add9 => Some((2, ChordFunction::Extension)),
+            Self::Add11 => Some((5, ChordFunction::Extension)),
+            Self::Add13 => Some((9, ChordFunction::Extension)),
+            Self::Suspended2 => Some((2, ChordFunction::Third)),
+            Self::Suspended4 => Some((5, ChordFunction::Third)),
+            Self::No5 => None,
+        }
+    }
+
+    fn replaces(self) -> &'static [u8] {
+        match self {
+            Self::Flat5 | Self::Sharp5 | Self::No5 => &[7],
+            Self::Flat9 | Self::Sharp9 => &[2],
+            Self::Sharp11 => &[5],
+            Self::Flat13 => &[9],
+            Self::Add9 | Self::Add11 | Self::Add13 => &[],
+            Self::Suspended2 | Self::Suspended4 => &[3, 4],
+        }
+    }
+
+    fn is_fifth(self) -> bool {
+        matches!(self.tone(), Some((_, ChordFunction::Fifth)))
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::No5 => "(no5)",
+            _ => self.aliases()[0],
+        }
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::Flat5 => &["b5", "♭5"],
+            Self::Sharp5 => &["#5", "♯5"],
+            Self::Flat9 => &["b9", "♭9"],
+            Self::Sharp9 => &["#9", "♯9"],
+            Self::Sharp11 => &["#11", "♯11"],
+            Self::Flat13 => &["b13", "♭13"],
+            Self::Add9 => &["add9", "add2"],
+            Self::Add11 => &["add11", "add4"],
+            Self::Add13 => &["add13", "add6"],
+            Self::Suspended2 => &["sus2"],
+            Self::Suspended4 => &["sus4", "sus"],
+            Self::No5 => &["no5", "omit5"],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ChordError {
+    Parse(String),
+}
+
+impl fmt::Display for ChordError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(value) => write!(out, "parse chord: {}", value),
+        }
+    }
+}
+
+impl error::Error for ChordError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuning::{FretPosition, Tuning};
+
+    fn parse(value: &str) -> Chord {
+        value.parse().unwrap()
+    }
+
+    fn semitones(chord: &Chord) -> Vec<u8> {
+        let mut result: Vec<u8> = chord.tones().iter().map(|x| x.interval.semitones()).collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn parse_examples() {
+        let chord = parse("Cmaj7");
+        assert_eq!((chord.root, chord.quality), (Note::C, ChordQuality::Major7));
+        let chord = parse("F#m7b5");
+        assert_eq!((chord.root, chord.quality), (Note::Gb, ChordQuality::HalfDiminished7));
+        let chord = parse("G7#9");
+        assert_eq!(chord.alterations, [ChordAlteration::Sharp9]);
+        assert_eq!(semitones(&chord), [0, 3, 4, 7, 10]);
+        let chord = parse("C/E");
+        assert_eq!((chord.quality, chord.bass), (ChordQuality::Major, Some(Note::E)));
+        let chord = parse("Am7(no5)");
+        assert_eq!(chord.alterations, [ChordAlteration::No5]);
+        assert_eq!(semitones(&chord), [0, 3, 10]);
+    }
+
+    #[test]
+    fn parse_six_nine() {
+        let chord = parse("C6/9");
+        assert_eq!((chord.quality, chord.bass), (ChordQuality::Major6Add9, None));
+        assert_eq!(semitones(&chord), [0, 2, 4, 7, 9]);
+        let chord = parse("Cm6/9/Eb");
+        assert_eq!((chord.quality, chord.bass), (ChordQuality::Minor6Add9, Some(Note::Eb)));
+    }
+
+    #[test]
+    fn parse_suspended_extensions() {
+        let chord = parse("C9sus4");
+        assert_eq!(chord.quality, ChordQuality::Dominant9);
+        assert_eq!(semitones(&chord), [0, 2, 5, 7, 10]);
+        assert_eq!(parse("C13sus").alterations, [ChordAlteration::Suspended4]);
+    }
+
+    /// Names the chord of fret positions of the standard guitar tuning, from the 6th string to the 1st.
+    fn identify(frets: [Option<u8>; 6]) -> Vec<String> {
+        let tuning = Tuning::default();
+        let pitches: Vec<Pitch> = frets
+            .iter()
+            .enumerate()
+            .filter_map(|(string_idx, fret)| tuning.get_pitch(FretPosition::new(string_idx, (*fret)?)))
+            .collect();
+        ChordCandidate::identify(&pitches)
+            .iter()
+            .map(|x| x.format(NoteFormat::Sharp))
+            .collect()
+    }
+
+    #[test]
+    fn identify_open_chords() {
+        assert_eq!(identify([None, Some(3), Some(2), Some(0), Some(1), Some(0)])[0], "C");
+        assert_eq!(identify([Some(0), Some(2), Some(2), Some(1), Some(0), Some(0)])[0], "E");
+        assert_eq!(identify([None, Some(0), Some(2), Some(0), Some(1), Some(0)])[0], "Am7");
+        assert_eq!(identify([None, Some(3), Some(5), Some(4), Some(5), None])[0], "Cmaj7");
+    }
+
+    #[test]
+    fn identify_inversions() {
+        assert_eq!(
+            identify([Some(0), Some(3), Some(2), Some(0), Some(1), Some(0)])[0],
+            "C/E"
+        );
+        assert_eq!(
+            identify([Some(3), Some(3), Some(2), Some(0), Some(1), Some(0)])[0],
+            "C/G"
+        );
+    }
+
+    #[test]
+    fn identify_omitted_fifth() {
+        assert_eq!(identify([Some(5), None, Some(5), Some(5), None, None])[0], "Am7 (no 5)");
+    }
+
+    #[test]
+    fn identify_altered_ninths() {
+        // E, B, D, G#, F, G also reads as G13b9/E.
+        let candidates = identify([Some(0), Some(2), Some(0), Some(1), Some(6), Some(3)]);
+        assert!(candidates.iter().any(|x| x == "E7b9#9"), "{:?}", candidates);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for value in ["", "H7", "C/X", "Cmaj7/", "Cfoo"] {
+            assert!(value.parse::<Chord>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn format_round_trip() {
+        for value in [
+            "Cmaj7",
+            "F#m7b5",
+            "G7#9",
+            "C/E",
+            "Am7(no5)",
+            "C6/9",
+            "C9sus4",
+            "Dm7b9#9/A",
+        ] {
+            assert_eq!(parse(value).to_string(), value);
+        }
+    }
+}
@@ -1,23 +1,97 @@
-use std::{env, error, fmt, fs, io, path};
+use std::{env, error, fmt, fs, io, path, str, sync::Arc};
 
-use miette::Report;
+use knus::span::{Span, Spanned};
+use miette::{
+    Diagnostic,
+    GraphicalReportHandler,
+    GraphicalTheme,
+    LabeledSpan,
+    NamedSource,
+    Report,
+    Severity,
+    SourceCode,
+};
 
 use crate::{
+    library,
+    suggestion::Expected,
+    tension::{UnitWeight, UnitWeights},
     theme::{ThemeError, ThemeName},
-    tuning::{NoteFormat, NoteFormatError, Pitch, Tuning, TuningCollection, TuningError},
+    tuning::{
+        Capo,
+        FretMarkerStyle,
+        FretMarkers,
+        FretSpacing,
+        NoteFormat,
+        NoteFormatError,
+        Pitch,
+        ScaleLength,
+        StringFrets,
+        StringGauge,
+        StringRange,
+        Tuning,
+        TuningCollection,
+        TuningError,
+    },
+    widget::{Orientation, OrientationError},
 };
 
 pub const APPLICATION_ID: &str = "com.rossnomann.fretboard";
 pub const APPLICATION_TITLE: &str = "Fretboard";
 
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct ConfigArgs {
+    /// Path to the configuration file, overrides FRETBOARD_CONFIG_PATH
+    #[arg(long, global = true)]
+    pub config: Option<path::PathBuf>,
+    /// Name of a tuning to select
+    #[arg(long, global = true)]
+    pub tuning: Option<String>,
+    /// Number of frets for every tuning
+    #[arg(long, global = true, value_parser = clap::value_parser!(u8).range(1..))]
+    pub frets: Option<u8>,
+    /// Note format: flat, sharp
+    #[arg(long, global = true)]
+    pub note_format: Option<String>,
+    /// Theme: catppuccin-frappe, catppuccin-latte, catppuccin-macchiato, catppuccin-mocha
+    #[arg(long, global = true)]
+    pub theme: Option<String>,
+    /// Orientation: auto, horizontal, vertical
+    #[arg(long, global = true)]
+    pub orientation: Option<String>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Config {
+    pub flip_strings: bool,
+    pub left_handed: bool,
     pub note_format: NoteFormat,
+    pub orientation: Orientation,
     pub tuning: TuningCollection,
     pub theme_name: ThemeName,
+    pub unit_weights: UnitWeights,
+    pub warnings: Vec<ConfigIssue>,
 }
 
 impl Config {
+    pub fn read(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        let config = match &args.config {
+            Some(config_path) => {
+                log::info!("Reading configuration from --config: {}", config_path.display());
+                Self::read_from_path(config_path)?
+            }
+            None => Self::read_from_file()?,
+        };
+        config.with_args(args)
+    }
+
+    pub fn get_path(args: &ConfigArgs) -> Option<path::PathBuf> {
+        args.config
+            .clone()
+            .or_else(|| env::var("FRETBOARD_CONFIG_PATH").ok().map(path::PathBuf::from))
+            .or_else(|| xdg::BaseDirectories::with_prefix("fretboard").get_config_file("config.kdl"))
+    }
+
     pub fn read_from_file() -> Result<Self, ConfigError> {
         let config_path = match env::var("FRETBOARD_CONFIG_PATH") {
             Ok(value) => {
@@ -46,113 +120,831 @@ impl Config {
             }
         });
         if let Some(config_path) = config_path {
-            let data = fs::read_to_string(&config_path)?;
-            let file_name = config_path
-                .file_name()
-                .map(|x| format!("{}", x.display()))
-                .unwrap_or(String::from("config.kdl"));
-            let schema: Schema = knus::parse(file_name, &data)?;
-            Self::try_from(schema)
+            Self::read_from_path(&config_path)
         } else {
             Ok(Self::default())
         }
     }
+
+    fn read_from_path(config_path: &path::Path) -> Result<Self, ConfigError> {
+        let data = fs::read_to_string(config_path)?;
+        Self::parse(Self::get_file_name(config_path), data)
+    }
+
+    fn parse(file_name: String, data: String) -> Result<Self, ConfigError> {
+        let schema: Schema = knus::parse(&file_name, &data)?;
+        let source = NamedSource::new(file_name, Arc::new(data));
+        let (errors, warnings): (Vec<_>, Vec<_>) = schema
+            .validate(&source)
+            .into_iter()
+            .partition(|x| x.severity == Severity::Error);
+        if !errors.is_empty() {
+            return Err(ConfigError::Invalid(errors));
+        }
+        warnings.iter().for_each(|x| log::warn!("{}", x));
+        let mut config = Self::try_from(schema)?;
+        config.warnings = warnings;
+        Ok(config)
+    }
+
+    fn get_file_name(config_path: &path::Path) -> String {
+        config_path
+            .file_name()
+            .map(|x| format!("{}", x.display()))
+            .unwrap_or(String::from("config.kdl"))
+    }
+
+    pub(crate) fn with_args(mut self, args: &ConfigArgs) -> Result<Self, ConfigError> {
+        if let Some(name) = &args.tuning {
+            self.tuning.select_by_name(name)?;
+        }
+        if let Some(frets) = args.frets {
+            let selected = self.tuning.get_selected().name.clone();
+            for tuning in &mut self.tuning.items {
+                match tuning.set_total_frets(frets) {
+                    Ok(()) => {}
+                    Err(err) if tuning.name == selected => return Err(err.into()),
+                    Err(err) => log::warn!("Frets are not changed for {}: {}", tuning.name, err),
+                }
+            }
+        }
+        if let Some(x) = &args.note_format {
+            self.note_format = x.parse()?;
+        }
+        if let Some(x) = &args.theme {
+            self.theme_name = x.parse()?;
+        }
+        if let Some(x) = &args.orientation {
+            self.orientation = x.parse()?;
+        }
+        Ok(self)
+    }
 }
 
 impl TryFrom<Schema> for Config {
     type Error = ConfigError;
 
     fn try_from(value: Schema) -> Result<Self, Self::Error> {
-        let default_total_frets = value.default.frets.unwrap_or(Tuning::DEFAULT_TOTAL_FRETS);
-        let tunings: Vec<Tuning> = value
-            .tuning
-            .into_iter()
-            .map(|x| x.try_into_tuning(default_total_frets))
-            .collect::<Result<_, ConfigError>>()?;
+        let defaults = value.default.get_tuning_defaults();
+        let mut tunings = value.try_into_tunings(&defaults)?;
+        // Tunings of the file go first and override library ones with the same name.
+        if value.default.library.unwrap_or(true) {
+            let library: Vec<Tuning> = library::get_tunings(&defaults)
+                .into_iter()
+                .filter(|x| !tunings.iter().any(|tuning| tuning.name == x.name))
+                .collect();
+            tunings.extend(library);
+        }
         let default_tuning = value
             .default
             .tuning
-            .and_then(|name| {
-                tunings
-                    .iter()
-                    .enumerate()
-                    .find(|(_idx, x)| x.name.as_str() == name)
-                    .map(|(idx, _x)| idx)
-            })
-            .unwrap_or(0);
+            .and_then(|name| tunings.iter().position(|x| x.name == *name))
+            .unwrap_or_default();
         Ok(Self {
-            note_format: match value.default.note_format {
-                Some(x) => x.parse()?,
-                None => NoteFormat::default(),
-            },
+            flip_strings: value.default.flip_strings.unwrap_or_default(),
+            left_handed: value.default.left_handed.unwrap_or_default(),
+            note_format: value.default.note_format.unwrap_or_default(),
+            orientation: value.default.orientation.unwrap_or_default(),
             tuning: TuningCollection::new(tunings, default_tuning)?,
-            theme_name: match value.default.theme_name {
-                Some(x) => x.parse()?,
-                None => ThemeName::default(),
-            },
+            theme_name: value.default.theme_name.unwrap_or_default(),
+            unit_weights: UnitWeights::default().with_items(value.unit_weight.iter().map(|x| UnitWeight {
+                gauge: x.gauge,
+                value: *x.value,
+            })),
+            warnings: Vec::new(),
         })
     }
 }
 
-#[derive(Clone, Debug, knus::Decode)]
-struct Schema {
-    #[knus(child)]
+#[derive(Clone, Debug)]
+pub struct ConfigDocument {
+    path: path::PathBuf,
+    exists: bool,
+    text: String,
+    nodes: Vec<DocumentNode>,
+    tunings: Vec<Tuning>,
+    defaults: TuningDefaults,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DocumentNode {
+    /// Start of the comment lines right above the node, the start of the node when there are none.
+    comment_start: usize,
+    span: Span,
+}
+
+impl DocumentNode {
+    fn new(text: &str, span: Span) -> Self {
+        let mut comment_start = span.0;
+        let line_start = text[..span.0].rfind('\n').map_or(0, |x| x + 1);
+        // A node which goes after another one on the same line has no comments of its own.
+        if text[line_start..span.0].trim().is_empty() {
+            let mut line_end = line_start;
+            while line_end > 0 {
+                let previous_start = text[..line_end - 1].rfind('\n').map_or(0, |x| x + 1);
+                if !text[previous_start..line_end - 1].trim_start().starts_with("//") {
+                    break;
+                }
+                comment_start = previous_start;
+                line_end = previous_start;
+            }
+        }
+        Self { comment_start, span }
+    }
+}
+
+impl ConfigDocument {
+    pub fn read(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        let path = Config::get_path(args).ok_or(ConfigError::UnknownPath)?;
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                exists: false,
+                text: String::from("default {\n}\n"),
+                nodes: Vec::new(),
+                tunings: Vec::new(),
+                defaults: TuningDefaults::default(),
+            });
+        }
+        let text = fs::read_to_string(&path)?;
+        Self::parse(path, text)
+    }
+
+    fn parse(path: path::PathBuf, text: String) -> Result<Self, ConfigError> {
+        let schema: Schema = knus::parse(Config::get_file_name(&path), &text)?;
+        // Tunings of instruments are not editable, they are written back as part of the instrument node.
+        let defaults = schema.default.get_tuning_defaults();
+        let nodes = schema.tuning.iter().map(|x| DocumentNode::new(&text, x.span)).collect();
+        let tunings = schema
+            .tuning
+            .iter()
+            .map(|x| x.try_into_tuning(&defaults))
+            .collect::<Result<_, ConfigError>>()?;
+        Ok(Self {
+            path,
+            exists: true,
+            text,
+            nodes,
+            tunings,
+            defaults,
+        })
+    }
+
+    pub fn get_path(&self) -> &path::Path {
+        &self.path
+    }
+
+    pub fn get_tunings(&self) -> Vec<DocumentTuning> {
+        self.tunings
+            .iter()
+            .enumerate()
+            .map(|(idx, tuning)| match self.nodes.get(idx) {
+                Some(_) => DocumentTuning {
+                    origin: Some(idx),
+                    modified: false,
+                    tuning: tuning.clone(),
+                },
+                None => DocumentTuning::new(tuning.clone()),
+            })
+            .collect()
+    }
+
+    /// The file is not written if it has been changed since it was read or if the result would not load.
+    pub fn write(&self, tunings: &[DocumentTuning]) -> Result<(), ConfigError> {
+        let text = self.format(tunings);
+        Config::parse(Config::get_file_name(&self.path), text.clone())?;
+        self.check_unchanged()?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, text)?;
+        log::info!("Tunings saved to {}", self.path.display());
+        Ok(())
+    }
+
+    fn check_unchanged(&self) -> Result<(), ConfigError> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(value) => Some(value),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let expected = self.exists.then_some(&self.text);
+        if text.as_ref() == expected {
+            Ok(())
+        } else {
+            Err(ConfigError::FileChanged)
+        }
+    }
+
+    fn format(&self, tunings: &[DocumentTuning]) -> String {
+        let mut result = String::new();
+        let mut offset = 0;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            result.push_str(&self.text[offset..node.comment_start]);
+            offset = node.span.1;
+            if let Some(tuning) = tunings.get(idx) {
+                self.push_tuning(&mut result, tuning);
+            }
+        }
+        let rest = &self.text[offset..];
+        if self.nodes.is_empty() {
+            result.push_str(rest);
+            if !result.is_empty() && !result.ends_with('\n') {
+                result.push('\n');
+            }
+        }
+        tunings
+            .iter()
+            .skip(self.nodes.len())
+            .for_each(|x| self.push_tuning(&mut result, x));
+        if !self.nodes.is_empty() {
+            result.push_str(rest);
+        }
+        result
+    }
+
+    fn push_tuning(&self, out: &mut String, value: &DocumentTuning) {
+        match value.origin.map(|idx| self.nodes[idx]) {
+            Some(node) => {
+                let Span(start, end) = node.span;
+                out.push_str(&self.text[node.comment_start..start]);
+                if value.modified {
+                    out.push_str(&self.format_tuning(&value.tuning));
+                } else {
+                    out.push_str(&self.text[start..end]);
+                }
+            }
+            None => out.push_str(&self.format_tuning(&value.tuning)),
+        }
+        // The span of the last node in a file may end without a line break.
+        if !out.ends_with('\n') && !out.ends_with(';') {
+            out.push('\n');
+        }
+    }
+
+    fn format_tuning(&self, tuning: &Tuning) -> String {
+        let mut result = format!("tuning name={}", KdlString(&tuning.name));
+        if tuning.total_frets != self.defaults.total_frets {
+            result.push_str(&format!(" frets={}", tuning.total_frets));
+        }
+        if tuning.fret_spacing != self.defaults.fret_spacing {
+            result.push_str(&format!(" fret-spacing={}", KdlString(tuning.fret_spacing.name())));
+        }
+        if let Some(x) = tuning.scale_length {
+            if x.is_fanned() {
+                result.push_str(&format!(
+                    " scale-length-bass={:?} scale-length-treble={:?}",
+                    x.bass, x.treble
+                ));
+            } else {
+                result.push_str(&format!(" scale-length={:?}", x.bass));
+            }
+            if x.neutral_fret > 0 {
+                result.push_str(&format!(" neutral-fret={}", x.neutral_fret));
+            }
+        }
+        if tuning.fret_markers != self.defaults.fret_markers {
+            result.push_str(&format!(
+                " fret-markers={}",
+                KdlString(&tuning.fret_markers.to_string())
+            ));
+        }
+        if tuning.fret_marker_style != self.defaults.fret_marker_style {
+            result.push_str(&format!(
+                " fret-marker-style={}",
+                KdlString(tuning.fret_marker_style.name())
+            ));
+        }
+        if !tuning.string_frets.is_empty() {
+            let frets: Vec<_> = (0..tuning.pitches.len()).map(|x| tuning.get_frets(x)).collect();
+            if frets.iter().any(|x| *x.start() > 0) {
+                let value = frets
+                    .iter()
+                    .map(|x| x.start().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                result.push_str(&format!(" start-frets={}", KdlString(&value)));
+            }
+            if tuning.string_frets.iter().any(|x| x.count.is_some()) {
+                let value = frets
+                    .iter()
+                    .map(|x| (x.end() - x.start()).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                result.push_str(&format!(" string-frets={}", KdlString(&value)));
+            }
+        }
+        if !tuning.gauges.is_empty() {
+            let value = tuning
+                .gauges
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            result.push_str(&format!(" gauges={}", KdlString(&value)));
+        }
+        if let Some(x) = tuning.capo {
+            result.push_str(&format!(" capo={}", x.fret));
+            if let Some(strings) = x.strings {
+                result.push_str(&format!(" capo-strings={}", KdlString(&strings.to_string())));
+            }
+        }
+        for pitch in &tuning.pitches {
+            result.push_str(&format!(" {}", KdlString(&pitch.to_string())));
+        }
+        result.push('\n');
+        result
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DocumentTuning {
+    origin: Option<usize>,
+    modified: bool,
+    tuning: Tuning,
+}
+
+impl DocumentTuning {
+    pub fn new(tuning: Tuning) -> Self {
+        Self {
+            origin: None,
+            modified: true,
+            tuning,
+        }
+    }
+
+    pub fn get(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    pub fn get_mut(&mut self) -> &mut Tuning {
+        self.modified = true;
+        &mut self.tuning
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FretList(Vec<u8>);
+
+impl FretList {
+    fn into_string_frets(start_frets: Option<&Self>, string_frets: Option<&Self>) -> Vec<StringFrets> {
+        let starts = start_frets.map_or(&[][..], |x| &x.0);
+        let counts = string_frets.map_or(&[][..], |x| &x.0);
+        (0..starts.len().max(counts.len()))
+            .map(|idx| StringFrets {
+                start: starts.get(idx).copied().unwrap_or_default(),
+                count: counts.get(idx).copied(),
+            })
+            .collect()
+    }
+}
+
+impl str::FromStr for FretList {
+    type Err = TuningError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split([' ', ','])
+            .filter(|x| !x.is_empty())
+            .map(|x| x.parse().map_err(|_| TuningError::ParseFretList(String::from(value))))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct GaugeList(Vec<StringGauge>);
+
+impl str::FromStr for GaugeList {
+    type Err = TuningError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split([' ', ','])
+            .filter(|x| !x.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+pub struct KdlString<'a>(pub &'a str);
+
+impl fmt::Display for KdlString<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "\"")?;
+        self.0.chars().try_for_each(|x| match x {
+            '"' => write!(out, "\\\""),
+            '\\' => write!(out, "\\\\"),
+            '\n' => write!(out, "\\n"),
+            '\r' => write!(out, "\\r"),
+            '\t' => write!(out, "\\t"),
+            x => write!(out, "{}", x),
+        })?;
+        write!(out, "\"")
+    }
+}
+
+#[derive(Clone, Debug, Default, knus::Decode)]
+#[knus(span_type = Span)]
+pub(crate) struct Schema {
+    #[knus(child, default)]
     default: SchemaDefault,
     #[knus(children(name = "tuning"))]
     tuning: Vec<SchemaTuning>,
+    #[knus(children(name = "instrument"))]
+    instrument: Vec<SchemaInstrument>,
+    #[knus(children(name = "unit-weight"))]
+    unit_weight: Vec<SchemaUnitWeight>,
 }
 
-#[derive(Clone, Debug, knus::Decode)]
+#[derive(Clone, Debug, Default, knus::Decode)]
+#[knus(span_type = Span)]
 struct SchemaDefault {
     #[knus(child, unwrap(argument))]
     frets: Option<u8>,
+    #[knus(child, unwrap(argument, str))]
+    fret_spacing: Option<FretSpacing>,
+    #[knus(child, unwrap(argument, str))]
+    fret_markers: Option<FretMarkers>,
+    #[knus(child, unwrap(argument, str))]
+    fret_marker_style: Option<FretMarkerStyle>,
     #[knus(child, unwrap(argument))]
-    tuning: Option<String>,
+    tuning: Option<Spanned<String, Span>>,
+    #[knus(child, unwrap(argument, str))]
+    note_format: Option<NoteFormat>,
+    #[knus(child, unwrap(argument, str))]
+    theme_name: Option<ThemeName>,
+    #[knus(child, unwrap(argument, str))]
+    orientation: Option<Orientation>,
     #[knus(child, unwrap(argument))]
-    note_format: Option<String>,
+    left_handed: Option<bool>,
     #[knus(child, unwrap(argument))]
-    theme_name: Option<String>,
+    flip_strings: Option<bool>,
+    #[knus(child, unwrap(argument))]
+    library: Option<bool>,
 }
 
 #[derive(Clone, Debug, knus::Decode)]
+#[knus(span_type = Span)]
+struct SchemaInstrument {
+    #[knus(argument)]
+    name: String,
+    #[knus(property)]
+    frets: Option<u8>,
+    #[knus(property, str)]
+    fret_spacing: Option<FretSpacing>,
+    #[knus(property, str)]
+    fret_markers: Option<FretMarkers>,
+    #[knus(property, str)]
+    fret_marker_style: Option<FretMarkerStyle>,
+    #[knus(property)]
+    scale_length: Option<Spanned<f32, Span>>,
+    #[knus(property)]
+    scale_length_bass: Option<Spanned<f32, Span>>,
+    #[knus(property)]
+    scale_length_treble: Option<Spanned<f32, Span>>,
+    #[knus(property)]
+    neutral_fret: Option<u8>,
+    #[knus(children(name = "tuning"))]
+    tuning: Vec<SchemaTuning>,
+}
+
+#[derive(Clone, Debug, knus::Decode)]
+#[knus(span_type = Span)]
 struct SchemaTuning {
+    #[knus(span)]
+    span: Span,
     #[knus(property)]
     frets: Option<u8>,
+    #[knus(property, str)]
+    fret_spacing: Option<FretSpacing>,
+    #[knus(property, str)]
+    fret_markers: Option<FretMarkers>,
+    #[knus(property, str)]
+    fret_marker_style: Option<FretMarkerStyle>,
+    #[knus(property)]
+    scale_length: Option<Spanned<f32, Span>>,
+    #[knus(property)]
+    scale_length_bass: Option<Spanned<f32, Span>>,
+    #[knus(property)]
+    scale_length_treble: Option<Spanned<f32, Span>>,
     #[knus(property)]
-    name: Option<String>,
-    #[knus(arguments)]
-    data: Vec<String>,
+    neutral_fret: Option<u8>,
+    #[knus(property)]
+    capo: Option<Spanned<u8, Span>>,
+    #[knus(property, str)]
+    capo_strings: Option<StringRange>,
+    #[knus(property, str)]
+    start_frets: Option<FretList>,
+    #[knus(property, str)]
+    string_frets: Option<FretList>,
+    #[knus(property, str)]
+    gauges: Option<GaugeList>,
+    #[knus(property)]
+    name: Option<Spanned<String, Span>>,
+    #[knus(arguments, str)]
+    data: Vec<Pitch>,
 }
 
-impl SchemaTuning {
-    fn try_into_tuning(self, default_total_frets: u8) -> Result<Tuning, ConfigError> {
-        let pitches: Vec<Pitch> = self
-            .data
+#[derive(Clone, Debug, knus::Decode)]
+#[knus(span_type = Span)]
+struct SchemaUnitWeight {
+    #[knus(argument, str)]
+    gauge: StringGauge,
+    #[knus(argument)]
+    value: Spanned<f32, Span>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TuningDefaults {
+    instrument: Option<String>,
+    total_frets: u8,
+    fret_spacing: FretSpacing,
+    fret_markers: FretMarkers,
+    fret_marker_style: FretMarkerStyle,
+    scale_length_bass: Option<f32>,
+    scale_length_treble: Option<f32>,
+    neutral_fret: Option<u8>,
+}
+
+impl Default for TuningDefaults {
+    fn default() -> Self {
+        Self {
+            instrument: None,
+            total_frets: Tuning::DEFAULT_TOTAL_FRETS,
+            fret_spacing: FretSpacing::default(),
+            fret_markers: FretMarkers::default(),
+            fret_marker_style: FretMarkerStyle::default(),
+            scale_length_bass: None,
+            scale_length_treble: None,
+            neutral_fret: None,
+        }
+    }
+}
+
+impl Schema {
+    pub(crate) fn try_into_tunings(&self, defaults: &TuningDefaults) -> Result<Vec<Tuning>, ConfigError> {
+        self.get_tunings_with(defaults)
             .into_iter()
-            .map(|x| x.parse::<Pitch>())
-            .collect::<Result<_, TuningError>>()?;
-        let total_frets = self.frets.unwrap_or(default_total_frets);
-        let name = self.name.unwrap_or_else(|| {
-            pitches.iter().fold(String::new(), |mut acc, x| {
+            .map(|(tuning, defaults)| tuning.try_into_tuning(&defaults))
+            .collect()
+    }
+
+    fn get_tunings(&self) -> Vec<(&SchemaTuning, TuningDefaults)> {
+        self.get_tunings_with(&self.default.get_tuning_defaults())
+    }
+
+    fn get_tunings_with(&self, defaults: &TuningDefaults) -> Vec<(&SchemaTuning, TuningDefaults)> {
+        let mut result: Vec<_> = self.tuning.iter().map(|x| (x, defaults.clone())).collect();
+        for instrument in &self.instrument {
+            let instrument_defaults = instrument.get_tuning_defaults(defaults);
+            result.extend(instrument.tuning.iter().map(|x| (x, instrument_defaults.clone())));
+        }
+        result.sort_by_key(|(x, _)| x.span.0);
+        result
+    }
+
+    pub(crate) fn validate(&self, source: &NamedSource<Arc<String>>) -> Vec<ConfigIssue> {
+        let mut result = Vec::new();
+        let tunings = self.get_tunings();
+        let names: Vec<String> = tunings
+            .iter()
+            .map(|(tuning, defaults)| tuning.get_name(defaults))
+            .collect();
+        for (idx, (tuning, defaults)) in tunings.iter().enumerate() {
+            if tuning.data.is_empty() {
+                result.push(ConfigIssue::error(source, tuning.span, "tuning has no strings"));
+            }
+            let total_frets = tuning.frets.unwrap_or(defaults.total_frets);
+            if total_frets == 0 {
+                result.push(ConfigIssue::error(source, tuning.span, "tuning has no frets"));
+            }
+            if let Some(capo) = &tuning.capo
+                && **capo > total_frets
+            {
+                let message = format!(
+                    "capo fret is out of range: {}, the tuning has {} frets",
+                    **capo, total_frets
+                );
+                result.push(ConfigIssue::error(source, *capo.span(), message));
+            }
+            if let Some(strings) = tuning.capo_strings
+                && strings.last > tuning.data.len()
+            {
+                let message = format!(
+                    "capo strings are out of range: {}, the tuning has {} strings",
+                    strings,
+                    tuning.data.len()
+                );
+                result.push(ConfigIssue::warning(source, tuning.span, message));
+            }
+            let lists = [
+                ("start-frets", tuning.start_frets.as_ref().map(|x| x.0.len())),
+                ("string-frets", tuning.string_frets.as_ref().map(|x| x.0.len())),
+                ("gauges", tuning.gauges.as_ref().map(|x| x.0.len())),
+            ];
+            for (property, len) in lists {
+                if let Some(len) = len
+                    && len != tuning.data.len()
+                {
+                    let message = format!(
+                        "{} has {} values, the tuning has {} strings",
+                        property,
+                        len,
+                        tuning.data.len()
+                    );
+                    result.push(ConfigIssue::error(source, tuning.span, message));
+                }
+            }
+            if let Some(start) = tuning
+                .start_frets
+                .iter()
+                .flat_map(|x| &x.0)
+                .find(|x| **x >= total_frets)
+            {
+                let message = format!(
+                    "string starts past the last fret: {}, the tuning has {} frets",
+                    start, total_frets
+                );
+                result.push(ConfigIssue::error(source, tuning.span, message));
+            }
+            let name = &names[idx];
+            if let Some(first_idx) = names[..idx].iter().position(|x| x == name) {
+                let message = format!(
+                    "duplicate tuning name: {}, first defined at line {}",
+                    name,
+                    ConfigIssue::get_location(source, tunings[first_idx].0.span).0
+                );
+                let span = tuning.name.as_ref().map_or(tuning.span, |x| *x.span());
+                result.push(ConfigIssue::warning(source, span, message));
+            }
+        }
+        let library_names: Vec<String> = if self.default.library.unwrap_or(true) {
+            library::get_tunings(&self.default.get_tuning_defaults())
+                .into_iter()
+                .map(|x| x.name)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        const MAX_TUNING_SUGGESTIONS: usize = 3;
+        if let Some(name) = &self.default.tuning
+            && !(names.is_empty() && library_names.is_empty())
+            && !names.contains(name)
+            && !library_names.contains(name)
+        {
+            let mut candidates: Vec<&str> = Vec::new();
+            for x in names.iter().chain(&library_names) {
+                if !candidates.contains(&x.as_str()) {
+                    candidates.push(x);
+                }
+            }
+            let message = format!(
+                "unknown default tuning: {}, {}",
+                **name,
+                Expected::new(name, &candidates).with_limit(MAX_TUNING_SUGGESTIONS)
+            );
+            result.push(ConfigIssue::warning(source, *name.span(), message));
+        }
+        let scale_lengths = self
+            .instrument
+            .iter()
+            .flat_map(|x| [x.scale_length, x.scale_length_bass, x.scale_length_treble])
+            .chain(
+                tunings
+                    .iter()
+                    .flat_map(|(x, _)| [x.scale_length, x.scale_length_bass, x.scale_length_treble]),
+            );
+        for value in scale_lengths.flatten() {
+            if *value <= 0.0 {
+                let message = format!("scale length must be positive: {}", *value);
+                result.push(ConfigIssue::error(source, *value.span(), message));
+            }
+        }
+        for unit_weight in &self.unit_weight {
+            if *unit_weight.value <= 0.0 {
+                let message = format!("unit weight must be positive: {}", *unit_weight.value);
+                result.push(ConfigIssue::error(source, *unit_weight.value.span(), message));
+            }
+        }
+        result
+    }
+}
+
+impl SchemaDefault {
+    fn get_tuning_defaults(&self) -> TuningDefaults {
+        TuningDefaults {
+            total_frets: self.frets.unwrap_or(Tuning::DEFAULT_TOTAL_FRETS),
+            fret_spacing: self.fret_spacing.unwrap_or_default(),
+            fret_markers: self.fret_markers.clone().unwrap_or_default(),
+            fret_marker_style: self.fret_marker_style.unwrap_or_default(),
+            ..TuningDefaults::default()
+        }
+    }
+}
+
+impl SchemaInstrument {
+    fn get_tuning_defaults(&self, parent: &TuningDefaults) -> TuningDefaults {
+        TuningDefaults {
+            instrument: Some(self.name.clone()),
+            total_frets: self.frets.unwrap_or(parent.total_frets),
+            fret_spacing: self.fret_spacing.unwrap_or(parent.fret_spacing),
+            fret_markers: self.fret_markers.clone().unwrap_or_else(|| parent.fret_markers.clone()),
+            fret_marker_style: self.fret_marker_style.unwrap_or(parent.fret_marker_style),
+            scale_length_bass: self.scale_length_bass.or(self.scale_length).map(|x| *x),
+            scale_length_treble: self.scale_length_treble.or(self.scale_length).map(|x| *x),
+            neutral_fret: self.neutral_fret,
+        }
+    }
+}
+
+impl SchemaTuning {
+    fn get_name(&self, defaults: &TuningDefaults) -> String {
+        let name = match &self.name {
+            Some(name) => (**name).clone(),
+            None => self.data.iter().fold(String::new(), |mut acc, x| {
                 acc.push_str(&x.to_string());
                 acc
-            })
+            }),
+        };
+        match &defaults.instrument {
+            Some(instrument) => format!("{} {}", instrument, name),
+            None => name,
+        }
+    }
+
+    fn try_into_tuning(&self, defaults: &TuningDefaults) -> Result<Tuning, ConfigError> {
+        let name = self.get_name(defaults);
+        let pitches = self.data.clone();
+        let total_frets = self.frets.unwrap_or(defaults.total_frets);
+        let fret_spacing = self.fret_spacing.unwrap_or(defaults.fret_spacing);
+        let scale_length = match (
+            self.scale_length_bass
+                .or(self.scale_length)
+                .map(|x| *x)
+                .or(defaults.scale_length_bass),
+            self.scale_length_treble
+                .or(self.scale_length)
+                .map(|x| *x)
+                .or(defaults.scale_length_treble),
+        ) {
+            (Some(bass), Some(treble)) => Some((bass, treble)),
+            (Some(x), None) | (None, Some(x)) => Some((x, x)),
+            (None, None) => None,
+        };
+        let scale_length = scale_length.map(|(bass, treble)| ScaleLength {
+            bass,
+            treble,
+            neutral_fret: self.neutral_fret.or(defaults.neutral_fret).unwrap_or_default(),
         });
+        let capo = match self.capo.map(|x| *x) {
+            Some(fret) if fret > 0 => Some(Capo {
+                fret,
+                strings: self.capo_strings,
+            }),
+            _ => None,
+        };
         Ok(Tuning {
             pitches,
             total_frets,
+            fret_spacing,
+            scale_length,
+            capo,
+            string_frets: FretList::into_string_frets(self.start_frets.as_ref(), self.string_frets.as_ref()),
+            gauges: self.gauges.clone().map(|x| x.0).unwrap_or_default(),
+            fret_markers: self
+                .fret_markers
+                .clone()
+                .unwrap_or_else(|| defaults.fret_markers.clone()),
+            fret_marker_style: self.fret_marker_style.unwrap_or(defaults.fret_marker_style),
             name,
+            instrument: defaults.instrument.clone(),
         })
     }
 }
 
 #[derive(Debug)]
 pub enum ConfigError {
+    FileChanged,
+    Invalid(Vec<ConfigIssue>),
     ParseKdl(Report),
     ParseNoteFormat(NoteFormatError),
+    ParseOrientation(OrientationError),
     ParseTheme(ThemeError),
     ParseTuning(TuningError),
     ReadFile(io::Error),
+    UnknownPath,
+}
+
+impl ConfigError {
+    pub fn format_report(&self) -> Option<String> {
+        match self {
+            Self::Invalid(issues) => Some(issues.iter().map(ConfigIssue::format_report).collect()),
+            Self::ParseKdl(report) => format_kdl_report(report),
+            _ => None,
+        }
+    }
 }
 
 impl From<knus::Error> for ConfigError {
@@ -167,6 +959,12 @@ impl From<NoteFormatError> for ConfigError {
     }
 }
 
+impl From<OrientationError> for ConfigError {
+    fn from(value: OrientationError) -> Self {
+        Self::ParseOrientation(value)
+    }
+}
+
 impl From<ThemeError> for ConfigError {
     fn from(value: ThemeError) -> Self {
         Self::ParseTheme(value)
@@ -188,11 +986,18 @@ impl From<io::Error> for ConfigError {
 impl fmt::Display for ConfigError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::ParseKdl(err) => write!(out, "{}", err),
+            Self::FileChanged => write!(out, "file has been changed since it was read"),
+            Self::Invalid(issues) => {
+                let messages: Vec<String> = issues.iter().map(ConfigIssue::to_string).collect();
+                write!(out, "{}", messages.join("; "))
+            }
+            Self::ParseKdl(err) => write!(out, "{}", KdlSummary(err)),
             Self::ParseNoteFormat(err) => write!(out, "parse note format: {}", err),
+            Self::ParseOrientation(err) => write!(out, "parse orientation: {}", err),
             Self::ParseTheme(err) => write!(out, "parse theme: {}", err),
             Self::ParseTuning(err) => write!(out, "parse tuning: {}", err),
             Self::ReadFile(err) => write!(out, "read file: {}", err),
+            Self::UnknownPath => write!(out, "configuration file path is not found"),
         }
     }
 }
@@ -200,11 +1005,287 @@ impl fmt::Display for ConfigError {
 impl error::Error for ConfigError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         Some(match self {
-            Self::ParseKdl(_) => return None,
+            Self::FileChanged | Self::Invalid(_) | Self::ParseKdl(_) | Self::UnknownPath => return None,
             Self::ParseNoteFormat(err) => err,
+            Self::ParseOrientation(err) => err,
             Self::ParseTheme(err) => err,
             Self::ParseTuning(err) => err,
             Self::ReadFile(err) => err,
         })
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    message: String,
+    source: NamedSource<Arc<String>>,
+    span: Span,
+}
+
+impl ConfigIssue {
+    fn error(source: &NamedSource<Arc<String>>, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            source: source.clone(),
+            span,
+        }
+    }
+
+    fn warning(source: &NamedSource<Arc<String>>, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(source, span, message)
+        }
+    }
+
+    fn get_location(source: &NamedSource<Arc<String>>, span: Span) -> (usize, usize) {
+        let before = &source.inner()[..span.0];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |x| x.chars().count()) + 1;
+        (line, column)
+    }
+
+    pub fn format_report(&self) -> String {
+        format_report(self).unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let (line, column) = Self::get_location(&self.source, self.span);
+        write!(out, "{}:{}:{}: {}", self.source.name(), line, column, self.message)
+    }
+}
+
+impl error::Error for ConfigIssue {}
+
+impl Diagnostic for ConfigIssue {
+    fn severity(&self) -> Option<Severity> {
+        Some(self.severity)
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let Span(start, end) = self.span;
+        Some(Box::new(std::iter::once(LabeledSpan::underline(start..end))))
+    }
+}
+
+fn format_report(diagnostic: &dyn Diagnostic) -> Option<String> {
+    let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
+    let mut result = String::new();
+    handler.render_report(&mut result, diagnostic).ok().map(|()| result)
+}
+
+/// Renders every error of a KDL report on its own, without the summary which only says that parsing failed.
+pub(crate) fn format_kdl_report(report: &Report) -> Option<String> {
+    match report.related() {
+        Some(related) => related
+            .map(|diagnostic| {
+                format_report(&KdlCause {
+                    diagnostic,
+                    source_code: report.source_code(),
+                })
+            })
+            .collect(),
+        None => format_report(report.as_ref()),
+    }
+}
+
+pub(crate) struct KdlSummary<'a>(pub(crate) &'a Report);
+
+impl fmt::Display for KdlSummary<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let messages: Vec<String> = match self.0.related() {
+            Some(related) => related.map(|x| x.to_string()).collect(),
+            None => Vec::new(),
+        };
+        if messages.is_empty() {
+            write!(out, "{}", self.0)
+        } else {
+            write!(out, "{}: {}", self.0, messages.join("; "))
+        }
+    }
+}
+
+/// A value which failed to convert repeats the message of the conversion error, so the cause would be shown twice.
+struct KdlCause<'a> {
+    diagnostic: &'a dyn Diagnostic,
+    source_code: Option<&'a dyn SourceCode>,
+}
+
+impl fmt::Debug for KdlCause<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.diagnostic, out)
+    }
+}
+
+impl fmt::Display for KdlCause<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.diagnostic, out)
+    }
+}
+
+impl error::Error for KdlCause<'_> {}
+
+impl Diagnostic for KdlCause<'_> {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.diagnostic.code()
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        self.diagnostic.severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.diagnostic.help()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.diagnostic.source_code().or(self.source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.diagnostic.labels()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"default {
+    frets 12
+}
+
+// Tuned down a whole step
+// for heavier strings
+tuning name="D Standard" "D2" "G2" "C3" "F3" "A3" "D4"
+
+tuning name="Open G" capo=2 "D2" "G2" "D3" "G3" "B3" "D4"
+// Trailing comment
+"#;
+
+    fn parse() -> ConfigDocument {
+        ConfigDocument::parse(path::PathBuf::from("config.kdl"), String::from(TEXT)).unwrap()
+    }
+
+    #[test]
+    fn format_identity() {
+        let document = parse();
+        assert_eq!(document.format(&document.get_tunings()), TEXT);
+    }
+
+    #[test]
+    fn format_reorder() {
+        let document = parse();
+        let mut tunings = document.get_tunings();
+        tunings.swap(0, 1);
+        let expected = r#"default {
+    frets 12
+}
+
+tuning name="Open G" capo=2 "D2" "G2" "D3" "G3" "B3" "D4"
+
+// Tuned down a whole step
+// for heavier strings
+tuning name="D Standard" "D2" "G2" "C3" "F3" "A3" "D4"
+// Trailing comment
+"#;
+        assert_eq!(document.format(&tunings), expected);
+    }
+
+    #[test]
+    fn format_delete() {
+        let document = parse();
+        let mut tunings = document.get_tunings();
+        tunings.remove(0);
+        let expected = r#"default {
+    frets 12
+}
+
+tuning name="Open G" capo=2 "D2" "G2" "D3" "G3" "B3" "D4"
+
+// Trailing comment
+"#;
+        assert_eq!(document.format(&tunings), expected);
+    }
+
+    #[test]
+    fn format_append() {
+        let document = parse();
+        let mut tunings = document.get_tunings();
+        tunings.push(DocumentTuning::new(Tuning {
+            name: String::from("New"),
+            total_frets: 12,
+            ..Tuning::default()
+        }));
+        tunings[0].get_mut().total_frets = 15;
+        let expected = r#"default {
+    frets 12
+}
+
+// Tuned down a whole step
+// for heavier strings
+tuning name="D Standard" frets=15 "D2" "G2" "C3" "F3" "A3" "D4"
+
+tuning name="Open G" capo=2 "D2" "G2" "D3" "G3" "B3" "D4"
+tuning name="New" "E2" "A2" "D3" "G3" "B3" "E4"
+// Trailing comment
+"#;
+        assert_eq!(document.format(&tunings), expected);
+    }
+
+    #[test]
+    fn format_capo() {
+        let document = parse();
+        let mut tunings = document.get_tunings();
+        tunings[0].get_mut().capo = Some(Capo {
+            fret: 3,
+            strings: Some(StringRange { first: 1, last: 5 }),
+        });
+        let config = Config::parse(String::from("config.kdl"), document.format(&tunings)).unwrap();
+        assert_eq!(config.tuning.get_selected().capo, tunings[0].get().capo);
+    }
+
+    #[test]
+    fn validate_scale_length() {
+        let text = r#"instrument "I" scale-length=-3.0 {
+    tuning name="A" "E2" "A2"
+}
+tuning name="B" scale-length-bass=0.0 scale-length-treble=25.5 "E2" "A2"
+"#;
+        let result = Config::parse(String::from("config.kdl"), String::from(text));
+        assert!(matches!(result, Err(ConfigError::Invalid(issues)) if issues.len() == 2));
+    }
+
+    #[test]
+    fn write_invalid() {
+        let document = parse();
+        let mut tunings = document.get_tunings();
+        tunings[1].get_mut().total_frets = 1;
+        let result = Config::parse(String::from("config.kdl"), document.format(&tunings));
+        assert!(matches!(result, Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn write_changed() {
+        let path = env::temp_dir().join(format!("fretboard-test-{}.kdl", std::process::id()));
+        fs::write(&path, TEXT).unwrap();
+        let args = ConfigArgs {
+            config: Some(path.clone()),
+            ..ConfigArgs::default()
+        };
+        let document = ConfigDocument::read(&args).unwrap();
+        assert!(document.write(&document.get_tunings()).is_ok());
+        fs::write(&path, format!("{}tuning \"E2\" \"A2\"\n", TEXT)).unwrap();
+        let result = document.write(&document.get_tunings());
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(ConfigError::FileChanged)));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::tuning::Note;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Interval(u8);
+
+impl Interval {
+    pub fn new(semitones: u8) -> Self {
+        Self(semitones % 12)
+    }
+
+    pub fn between(from: Note, to: Note) -> Self {
+        Self::new(12 + to.index() - from.index())
+    }
+
+    pub fn semitones(self) -> u8 {
+        self.0
+    }
+
+    pub fn name(self) -> &'static str {
+        match self.0 {
+            0 => "R",
+            _ => self.degree(),
+        }
+    }
+
+    pub fn degree(self) -> &'static str {
+        match self.0 {
+            0 => "1",
+            1 => "b2",
+            2 => "2",
+            3 => "b3",
+            4 => "3",
+            5 => "4",
+            6 => "b5",
+            7 => "5",
+            8 => "b6",
+            9 => "6",
+            10 => "b7",
+            _ => "7",
+        }
+    }
+
+    pub fn get_extension_degree(self) -> &'static str {
+        match self.0 {
+            1..=3 => "9",
+            5 | 6 => "11",
+            8 | 9 => "13",
+            _ => self.degree().trim_start_matches('b'),
+        }
+    }
+}
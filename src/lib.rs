@@ -1,7 +1,19 @@
 mod app;
+pub mod chord;
 mod config;
-mod theme;
-mod tuning;
-mod widget;
+pub mod interval;
+mod library;
+mod render;
+pub mod scale;
+mod session;
+mod suggestion;
+mod tension;
+pub mod theme;
+pub mod tuning;
+pub mod widget;
 
-pub use self::app::{AppError, run};
+pub use self::{
+    app::{AppError, run},
+    config::ConfigArgs,
+    render::{RenderArgs, RenderError, render},
+};
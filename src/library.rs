@@ -0,0 +1,46 @@
+use std::sync::LazyLock;
+
+use crate::{
+    config::{Schema, TuningDefaults},
+    tuning::Tuning,
+};
+
+static LIBRARY: LazyLock<Schema> = LazyLock::new(read_library);
+
+pub fn get_tunings(defaults: &TuningDefaults) -> Vec<Tuning> {
+    LIBRARY.try_into_tunings(defaults).unwrap_or_else(|err| {
+        log::error!("Could not read the tuning library: {}", err);
+        Vec::new()
+    })
+}
+
+fn read_library() -> Schema {
+    match knus::parse("tunings.kdl", include_str!("../resources/tunings.kdl")) {
+        Ok(value) => value,
+        Err(err) => {
+            log::error!("Could not parse the tuning library: {:?}", miette::Report::new(err));
+            Schema::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use miette::NamedSource;
+
+    use super::*;
+
+    #[test]
+    fn library_is_valid() {
+        let text = include_str!("../resources/tunings.kdl");
+        let schema: Schema = knus::parse("tunings.kdl", text).unwrap_or_else(|err| {
+            panic!("{:?}", miette::Report::new(err));
+        });
+        let issues = schema.validate(&NamedSource::new("tunings.kdl", Arc::new(String::from(text))));
+        assert!(issues.is_empty(), "{:?}", issues);
+        let tunings = get_tunings(&TuningDefaults::default());
+        assert_eq!(tunings.len(), text.matches("tuning name=").count());
+    }
+}
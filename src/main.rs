@@ -1,4 +1,38 @@
-fn main() -> Result<(), fretboard::AppError> {
+use std::process;
+
+use clap::Parser;
+
+/// A flexible fretboard visualization tool
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(flatten)]
+    config: fretboard::ConfigArgs,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Render the fretboard to an image without opening a window
+    Render(fretboard::RenderArgs),
+}
+
+fn main() -> process::ExitCode {
     env_logger::init();
-    fretboard::run()
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(Command::Render(args)) => fretboard::render(cli.config, args).map_err(fretboard::AppError::from),
+        None => fretboard::run(cli.config),
+    };
+    match result {
+        Ok(()) => process::ExitCode::SUCCESS,
+        Err(err) => {
+            match err.format_report() {
+                Some(report) => eprint!("{}", report),
+                None => eprintln!("error: {}", err),
+            }
+            process::ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use std::{error, fmt, fs, io, path};
+
+use crate::{
+    config::{Config, ConfigArgs, ConfigError},
+    widget::Fretboard,
+};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct RenderArgs {
+    /// Width of the image in pixels, the height is calculated from the fretboard proportions
+    #[arg(long, default_value_t = 1600)]
+    pub width: u32,
+    /// Output file, PNG unless the extension is svg
+    #[arg(long)]
+    pub out: path::PathBuf,
+}
+
+pub fn render(config_args: ConfigArgs, args: RenderArgs) -> Result<(), RenderError> {
+    let config = Config::read(&config_args)?;
+    let tuning = config.tuning.get_selected().clone();
+    let fretboard = Fretboard::<()>::new(tuning, config.note_format, config.theme_name)
+        .with_orientation(config.orientation)
+        .with_left_handed(config.left_handed)
+        .with_flipped_strings(config.flip_strings);
+    let size = fretboard.get_natural_size(args.width as f32);
+    let size = iced::Size::new(size.width.round(), size.height.round());
+    let svg = fretboard.to_svg(size);
+
+    if args.out.extension().is_some_and(|x| x.eq_ignore_ascii_case("svg")) {
+        fs::write(&args.out, svg)?;
+    } else {
+        let data = rasterize(&svg, size)?;
+        fs::write(&args.out, data)?;
+    }
+    log::info!(
+        "Rendered {}x{} fretboard to {}",
+        size.width,
+        size.height,
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn rasterize(svg: &str, size: iced::Size) -> Result<Vec<u8>, RenderError> {
+    let mut options = resvg::usvg::Options::default();
+    let fontdb = options.fontdb_mut();
+    fontdb.load_system_fonts();
+    // Generic families are mapped to fonts which may not be installed, so the first monospaced face is used instead.
+    let monospace_family = fontdb
+        .faces()
+        .find(|x| x.monospaced)
+        .and_then(|x| x.families.first())
+        .map(|(family, _)| family.clone());
+    match monospace_family {
+        Some(family) => fontdb.set_monospace_family(family),
+        None => log::warn!("No monospaced fonts found, note labels will not have text"),
+    }
+    let tree = resvg::usvg::Tree::from_str(svg, &options)?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width as u32, size.height as u32)
+        .ok_or(RenderError::InvalidSize(size.width as u32, size.height as u32))?;
+    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|err| RenderError::EncodePng(Box::new(err)))
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    Config(ConfigError),
+    EncodePng(Box<dyn error::Error>),
+    InvalidSize(u32, u32),
+    ParseSvg(resvg::usvg::Error),
+    WriteFile(io::Error),
+}
+
+impl RenderError {
+    pub fn format_report(&self) -> Option<String> {
+        match self {
+            Self::Config(err) => err.format_report(),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConfigError> for RenderError {
+    fn from(value: ConfigError) -> Self {
+        Self::Config(value)
+    }
+}
+
+impl From<resvg::usvg::Error> for RenderError {
+    fn from(value: resvg::usvg::Error) -> Self {
+        Self::ParseSvg(value)
+    }
+}
+
+impl From<io::Error> for RenderError {
+    fn from(value: io::Error) -> Self {
+        Self::WriteFile(value)
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Config(err) => err.fmt(out),
+            Self::EncodePng(err) => write!(out, "encode png: {}", err),
+            Self::InvalidSize(width, height) => write!(out, "invalid image size: {}x{}", width, height),
+            Self::ParseSvg(err) => write!(out, "parse svg: {}", err),
+            Self::WriteFile(err) => write!(out, "write file: {}", err),
+        }
+    }
+}
+
+impl error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(match self {
+            Self::Config(err) => err,
+            Self::EncodePng(err) => err.as_ref(),
+            Self::InvalidSize(_, _) => return None,
+            Self::ParseSvg(err) => err,
+            Self::WriteFile(err) => err,
+        })
+    }
+}
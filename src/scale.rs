@@ -0,0 +1,125 @@
+use std::fmt;
+
+use crate::{interval::Interval, tuning::Note};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Scale {
+    pub root: Note,
+    pub kind: ScaleKind,
+}
+
+impl Scale {
+    const MAJOR: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+    pub fn new(root: Note, kind: ScaleKind) -> Self {
+        Self { root, kind }
+    }
+
+    pub fn contains(&self, note: Note) -> bool {
+        let interval = Interval::between(self.root, note).semitones();
+        self.kind.intervals().contains(&interval)
+    }
+
+    pub fn get_degree(&self, note: Note) -> String {
+        let interval = Interval::between(self.root, note);
+        let intervals = self.kind.intervals();
+        if intervals.len() == Self::MAJOR.len()
+            && let Some(idx) = intervals.iter().position(|x| *x == interval.semitones())
+        {
+            let accidental = match intervals[idx] as i8 - Self::MAJOR[idx] as i8 {
+                -2 => "bb",
+                -1 => "b",
+                1 => "#",
+                2 => "##",
+                _ => "",
+            };
+            format!("{}{}", accidental, idx + 1)
+        } else {
+            String::from(interval.degree())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScaleKind {
+    #[default]
+    Chromatic,
+    Major,
+    NaturalMinor,
+    HarmonicMinor,
+    MelodicMinor,
+    MajorPentatonic,
+    MinorPentatonic,
+    Blues,
+    Ionian,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Aeolian,
+    Locrian,
+}
+
+impl ScaleKind {
+    pub const ALL: [ScaleKind; 15] = [
+        Self::Chromatic,
+        Self::Major,
+        Self::NaturalMinor,
+        Self::HarmonicMinor,
+        Self::MelodicMinor,
+        Self::MajorPentatonic,
+        Self::MinorPentatonic,
+        Self::Blues,
+        Self::Ionian,
+        Self::Dorian,
+        Self::Phrygian,
+        Self::Lydian,
+        Self::Mixolydian,
+        Self::Aeolian,
+        Self::Locrian,
+    ];
+
+    pub fn intervals(self) -> &'static [u8] {
+        match self {
+            Self::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            Self::Major | Self::Ionian => &[0, 2, 4, 5, 7, 9, 11],
+            Self::NaturalMinor | Self::Aeolian => &[0, 2, 3, 5, 7, 8, 10],
+            Self::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            Self::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Self::MajorPentatonic => &[0, 2, 4, 7, 9],
+            Self::MinorPentatonic => &[0, 3, 5, 7, 10],
+            Self::Blues => &[0, 3, 5, 6, 7, 10],
+            Self::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Self::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Self::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Self::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Self::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+        }
+    }
+}
+
+impl fmt::Display for ScaleKind {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "{}",
+            match self {
+                Self::Chromatic => "Chromatic",
+                Self::Major => "Major",
+                Self::NaturalMinor => "Natural Minor",
+                Self::HarmonicMinor => "Harmonic Minor",
+                Self::MelodicMinor => "Melodic Minor",
+                Self::MajorPentatonic => "Major Pentatonic",
+                Self::MinorPentatonic => "Minor Pentatonic",
+                Self::Blues => "Blues",
+                Self::Ionian => "Ionian",
+                Self::Dorian => "Dorian",
+                Self::Phrygian => "Phrygian",
+                Self::Lydian => "Lydian",
+                Self::Mixolydian => "Mixolydian",
+                Self::Aeolian => "Aeolian",
+                Self::Locrian => "Locrian",
+            }
+        )
+    }
+}
@@ -0,0 +1,155 @@
+use std::{env, error, fmt, fs, io, path};
+
+use miette::Report;
+
+use crate::{
+    config::{KdlString, KdlSummary},
+    theme::ThemeName,
+    tuning::NoteFormat,
+    widget::{LabelMode, Orientation},
+};
+
+#[derive(Clone, Debug, Default, knus::Decode)]
+pub struct Session {
+    #[knus(child, unwrap(argument))]
+    pub tuning: Option<String>,
+    #[knus(child, unwrap(argument, str))]
+    pub note_format: Option<NoteFormat>,
+    #[knus(child, unwrap(argument, str))]
+    pub theme_name: Option<ThemeName>,
+    #[knus(child, unwrap(argument, str))]
+    pub orientation: Option<Orientation>,
+    #[knus(child, unwrap(argument, str))]
+    pub label_mode: Option<LabelMode>,
+    #[knus(child, unwrap(argument))]
+    pub left_handed: Option<bool>,
+    #[knus(child, unwrap(argument))]
+    pub flip_strings: Option<bool>,
+    #[knus(child, unwrap(argument))]
+    pub hide_unhighlighted: Option<bool>,
+    #[knus(child, unwrap(argument))]
+    pub show_tension: Option<bool>,
+    #[knus(child)]
+    pub window: Option<SessionWindow>,
+}
+
+/// Size and position of the window in logical pixels, the position is not available on Wayland.
+#[derive(Clone, Copy, Debug, knus::Decode)]
+pub struct SessionWindow {
+    #[knus(property)]
+    pub width: u32,
+    #[knus(property)]
+    pub height: u32,
+    #[knus(property)]
+    pub x: Option<i32>,
+    #[knus(property)]
+    pub y: Option<i32>,
+}
+
+impl Session {
+    const FILE_NAME: &str = "session.kdl";
+
+    pub fn read() -> Result<Self, SessionError> {
+        let Some(session_path) = Self::get_path() else {
+            return Ok(Self::default());
+        };
+        if !session_path.exists() {
+            return Ok(Self::default());
+        }
+        log::info!("Reading session from {}", session_path.display());
+        let data = fs::read_to_string(&session_path).map_err(SessionError::ReadFile)?;
+        Ok(knus::parse(Self::FILE_NAME, &data)?)
+    }
+
+    pub fn write(&self) -> Result<(), SessionError> {
+        let session_path = match env::var("FRETBOARD_SESSION_PATH") {
+            Ok(value) => path::PathBuf::from(value),
+            Err(_) => xdg::BaseDirectories::with_prefix("fretboard")
+                .place_state_file(Self::FILE_NAME)
+                .map_err(SessionError::WriteFile)?,
+        };
+        fs::write(&session_path, self.to_string()).map_err(SessionError::WriteFile)?;
+        log::info!("Session saved to {}", session_path.display());
+        Ok(())
+    }
+
+    fn get_path() -> Option<path::PathBuf> {
+        env::var("FRETBOARD_SESSION_PATH")
+            .ok()
+            .map(path::PathBuf::from)
+            .or_else(|| xdg::BaseDirectories::with_prefix("fretboard").get_state_file(Self::FILE_NAME))
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(x) = &self.tuning {
+            writeln!(out, "tuning {}", KdlString(x))?;
+        }
+        if let Some(x) = self.note_format {
+            writeln!(out, "note-format {}", KdlString(x.name()))?;
+        }
+        if let Some(x) = self.theme_name {
+            writeln!(out, "theme-name {}", KdlString(x.name()))?;
+        }
+        if let Some(x) = self.orientation {
+            writeln!(out, "orientation {}", KdlString(x.name()))?;
+        }
+        if let Some(x) = self.label_mode {
+            writeln!(out, "label-mode {}", KdlString(x.name()))?;
+        }
+        if let Some(x) = self.left_handed {
+            writeln!(out, "left-handed {}", x)?;
+        }
+        if let Some(x) = self.flip_strings {
+            writeln!(out, "flip-strings {}", x)?;
+        }
+        if let Some(x) = self.hide_unhighlighted {
+            writeln!(out, "hide-unhighlighted {}", x)?;
+        }
+        if let Some(x) = self.show_tension {
+            writeln!(out, "show-tension {}", x)?;
+        }
+        if let Some(x) = self.window {
+            write!(out, "window width={} height={}", x.width, x.height)?;
+            if let (Some(x), Some(y)) = (x.x, x.y) {
+                write!(out, " x={} y={}", x, y)?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    ParseKdl(Report),
+    ReadFile(io::Error),
+    WriteFile(io::Error),
+}
+
+impl From<knus::Error> for SessionError {
+    fn from(value: knus::Error) -> Self {
+        Self::ParseKdl(Report::new(value))
+    }
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ParseKdl(err) => write!(out, "{}", KdlSummary(err)),
+            Self::ReadFile(err) => write!(out, "read file: {}", err),
+            Self::WriteFile(err) => write!(out, "write file: {}", err),
+        }
+    }
+}
+
+impl error::Error for SessionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(match self {
+            Self::ParseKdl(_) => return None,
+            Self::ReadFile(err) => err,
+            Self::WriteFile(err) => err,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+pub struct Expected<'a> {
+    value: &'a str,
+    candidates: &'a [&'a str],
+    limit: Option<usize>,
+}
+
+impl<'a> Expected<'a> {
+    pub fn new(value: &'a str, candidates: &'a [&'a str]) -> Self {
+        Self {
+            value,
+            candidates,
+            limit: None,
+        }
+    }
+
+    pub fn with_limit(mut self, value: usize) -> Self {
+        self.limit = Some(value);
+        self
+    }
+
+    fn find_similar(&self) -> Option<&'a str> {
+        let max_distance = (self.value.chars().count() / 3).max(2);
+        self.candidates
+            .iter()
+            .map(|x| (calculate_distance(self.value, x), *x))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, x)| x)
+    }
+}
+
+impl fmt::Display for Expected<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self.find_similar() {
+            Some(value) => write!(out, "did you mean \"{}\"?", value),
+            None => match self.limit {
+                Some(limit) => {
+                    // Long candidates are ranked by their part which is the closest to the value.
+                    let mut candidates: Vec<_> = self
+                        .candidates
+                        .iter()
+                        .map(|x| {
+                            let distance = (
+                                calculate_alignment(self.value, x, true),
+                                calculate_distance(self.value, x),
+                            );
+                            (distance, *x)
+                        })
+                        .collect();
+                    candidates.sort_by_key(|(distance, _)| *distance);
+                    let closest: Vec<_> = candidates.into_iter().take(limit).map(|(_, x)| x).collect();
+                    write!(out, "closest ones: {}", closest.join(", "))
+                }
+                None => write!(out, "expected one of: {}", self.candidates.join(", ")),
+            },
+        }
+    }
+}
+
+fn calculate_distance(a: &str, b: &str) -> usize {
+    calculate_alignment(a, b, false)
+}
+
+/// Returns the Levenshtein distance between `a` and `b`, or between `a` and the closest substring of `b`.
+fn calculate_alignment(a: &str, b: &str, substring: bool) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    // Characters of `b` before the substring are skipped for free.
+    let mut row: Vec<usize> = if substring {
+        vec![0; b.len() + 1]
+    } else {
+        (0..=b.len()).collect()
+    };
+    for (i, x) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    // So are the characters after it.
+    if substring {
+        row.into_iter().min().unwrap_or_default()
+    } else {
+        row[b.len()]
+    }
+}
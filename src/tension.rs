@@ -0,0 +1,136 @@
+use std::{error, fmt, sync::LazyLock};
+
+use crate::tuning::{Pitch, StringGauge, Tuning};
+
+static UNIT_WEIGHTS: LazyLock<Vec<UnitWeight>> = LazyLock::new(read_unit_weights);
+
+fn read_unit_weights() -> Vec<UnitWeight> {
+    match knus::parse::<Vec<ResourceUnitWeight>>("unit-weights.kdl", include_str!("../resources/unit-weights.kdl")) {
+        Ok(value) => value
+            .into_iter()
+            .map(|x| UnitWeight {
+                gauge: x.gauge,
+                value: x.value,
+            })
+            .collect(),
+        Err(err) => {
+            log::error!("Could not parse the unit weight table: {:?}", miette::Report::new(err));
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, knus::Decode)]
+struct ResourceUnitWeight {
+    #[knus(argument, str)]
+    gauge: StringGauge,
+    #[knus(argument)]
+    value: f32,
+}
+
+/// Mass of a string per inch of its length in pounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitWeight {
+    pub gauge: StringGauge,
+    pub value: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnitWeights {
+    items: Vec<UnitWeight>,
+}
+
+impl UnitWeights {
+    pub fn with_items(mut self, items: impl IntoIterator<Item = UnitWeight>) -> Self {
+        for item in items {
+            match self.items.iter_mut().find(|x| x.gauge == item.gauge) {
+                Some(x) => *x = item,
+                None => self.items.push(item),
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, gauge: StringGauge) -> Option<f32> {
+        // The weight grows with the square of the diameter.
+        self.items
+            .iter()
+            .filter(|x| x.gauge.wound == gauge.wound)
+            .min_by_key(|x| x.gauge.value.abs_diff(gauge.value))
+            .map(|x| x.value * (gauge.value as f32 / x.gauge.value as f32).powi(2))
+    }
+}
+
+impl Default for UnitWeights {
+    fn default() -> Self {
+        Self {
+            items: UNIT_WEIGHTS.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StringTension {
+    pub string_idx: usize,
+    pub pitch: Pitch,
+    pub gauge: StringGauge,
+    pub pounds: f32,
+}
+
+impl StringTension {
+    const KILOGRAMS_PER_POUND: f32 = 0.453_592_37;
+    /// Gravitational acceleration in inches per second squared, converts the tension into pounds-force.
+    const GRAVITY: f32 = 386.4;
+
+    /// Calculates the tension of every string: T = UW * (2 * L * F)^2 / 386.4.
+    pub fn calculate(tuning: &Tuning, unit_weights: &UnitWeights) -> Result<Vec<Self>, TensionError> {
+        let scale_length = tuning.scale_length.ok_or(TensionError::NoScaleLength)?;
+        if tuning.gauges.len() != tuning.pitches.len() {
+            return Err(TensionError::NoGauges);
+        }
+        let strings_count = tuning.pitches.len();
+        tuning
+            .pitches
+            .iter()
+            .zip(&tuning.gauges)
+            .enumerate()
+            .map(|(string_idx, (pitch, gauge))| {
+                let unit_weight = unit_weights
+                    .get(*gauge)
+                    .ok_or(TensionError::UnknownUnitWeight(*gauge))?;
+                let start = *tuning.get_frets(string_idx).start();
+                let length =
+                    scale_length.get_string_length(string_idx, strings_count) * 2f32.powf(-(start as f32) / 12.0);
+                Ok(Self {
+                    string_idx,
+                    pitch: *pitch,
+                    gauge: *gauge,
+                    pounds: unit_weight * (2.0 * length * pitch.get_frequency()).powi(2) / Self::GRAVITY,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_kilograms(&self) -> f32 {
+        self.pounds * Self::KILOGRAMS_PER_POUND
+    }
+}
+
+#[derive(Debug)]
+pub enum TensionError {
+    NoGauges,
+    NoScaleLength,
+    UnknownUnitWeight(StringGauge),
+}
+
+impl fmt::Display for TensionError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoGauges => write!(out, "tuning has no gauges for some of the strings"),
+            Self::NoScaleLength => write!(out, "tuning has no scale length"),
+            Self::UnknownUnitWeight(gauge) => write!(out, "unknown unit weight of gauge: {}", gauge),
+        }
+    }
+}
+
+impl error::Error for TensionError {}
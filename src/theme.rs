@@ -1,6 +1,8 @@
 use std::{error, fmt, str};
 
-#[derive(Clone, Copy, Debug, Default)]
+use crate::suggestion::Expected;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub enum ThemeName {
     Frappe,
     Latte,
@@ -9,6 +11,34 @@ pub enum ThemeName {
     Mocha,
 }
 
+impl ThemeName {
+    pub const ALL: [ThemeName; 4] = [Self::Frappe, Self::Latte, Self::Macchiato, Self::Mocha];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Frappe => "catppuccin-frappe",
+            Self::Latte => "catppuccin-latte",
+            Self::Macchiato => "catppuccin-macchiato",
+            Self::Mocha => "catppuccin-mocha",
+        }
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "{}",
+            match self {
+                Self::Frappe => "Catppuccin Frappé",
+                Self::Latte => "Catppuccin Latte",
+                Self::Macchiato => "Catppuccin Macchiato",
+                Self::Mocha => "Catppuccin Mocha",
+            }
+        )
+    }
+}
+
 impl From<ThemeName> for iced::Theme {
     fn from(value: ThemeName) -> Self {
         match value {
@@ -36,13 +66,10 @@ impl str::FromStr for ThemeName {
     type Err = ThemeError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        Ok(match value {
-            "catppuccin-frappe" => Self::Frappe,
-            "catppuccin-latte" => Self::Latte,
-            "catppuccin-macchiato" => Self::Macchiato,
-            "catppuccin-mocha" => Self::Mocha,
-            _ => return Err(ThemeError::Unknown(String::from(value))),
-        })
+        Self::ALL
+            .into_iter()
+            .find(|x| x.name() == value)
+            .ok_or_else(|| ThemeError::Unknown(String::from(value)))
     }
 }
 
@@ -122,7 +149,10 @@ pub enum ThemeError {
 impl fmt::Display for ThemeError {
     fn fmt(&self, out: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Unknown(name) => write!(out, "unknown theme: {}", name),
+            Self::Unknown(name) => {
+                let names = ThemeName::ALL.map(ThemeName::name);
+                write!(out, "unknown theme: {}, {}", name, Expected::new(name, &names))
+            }
         }
     }
 }
@@ -1,10 +1,11 @@
 use std::{
     error,
     fmt,
+    ops::RangeInclusive,
     str::{self, FromStr},
 };
 
-use crate::theme::Palette;
+use crate::{config::TuningDefaults, interval::Interval, library, suggestion::Expected, theme::Palette};
 
 #[derive(Clone, Debug)]
 pub struct TuningCollection {
@@ -30,6 +31,13 @@ impl TuningCollection {
         }
     }
 
+    pub fn select_by_name(&mut self, name: &str) -> Result<(), TuningError> {
+        match self.items.iter().position(|x| x.name == name) {
+            Some(idx) => self.select(idx),
+            None => Err(TuningError::UnknownName(String::from(name))),
+        }
+    }
+
     pub fn get_selected(&self) -> &Tuning {
         &self.items[self.selected_idx]
     }
@@ -37,10 +45,11 @@ impl TuningCollection {
 
 impl Default for TuningCollection {
     fn default() -> Self {
-        Self {
-            items: vec![Tuning::default()],
-            selected_idx: 0,
+        let mut items = library::get_tunings(&TuningDefaults::default());
+        if items.is_empty() {
+            items.push(Tuning::default());
         }
+        Self { items, selected_idx: 0 }
     }
 }
 
@@ -48,11 +57,60 @@ impl Default for TuningCollection {
 pub struct Tuning {
     pub pitches: Vec<Pitch>,
     pub total_frets: u8,
+    pub fret_spacing: FretSpacing,
+    pub scale_length: Option<ScaleLength>,
+    pub capo: Option<Capo>,
+    /// Frets of strings in the order of pitches, strings without an entry run the whole neck.
+    pub string_frets: Vec<StringFrets>,
+    pub gauges: Vec<StringGauge>,
+    pub fret_markers: FretMarkers,
+    pub fret_marker_style: FretMarkerStyle,
     pub name: String,
+    pub instrument: Option<String>,
 }
 
 impl Tuning {
     pub const DEFAULT_TOTAL_FRETS: u8 = 24;
+
+    pub fn get_pitch(&self, position: FretPosition) -> Option<Pitch> {
+        let frets = self.get_frets(position.string_idx);
+        if !frets.contains(&position.fret) {
+            return None;
+        }
+        self.pitches
+            .get(position.string_idx)
+            .and_then(|x| x.into_iter().nth((position.fret - frets.start()) as usize))
+    }
+
+    pub fn get_frets(&self, string_idx: usize) -> RangeInclusive<u8> {
+        match self.string_frets.get(string_idx) {
+            Some(x) => {
+                let start = x.start.min(self.total_frets);
+                let end = x.count.map_or(self.total_frets, |count| start.saturating_add(count));
+                start..=end.min(self.total_frets)
+            }
+            None => 0..=self.total_frets,
+        }
+    }
+
+    /// A capo past the last fret is removed, it fails when a short string would start past it.
+    pub fn set_total_frets(&mut self, value: u8) -> Result<(), TuningError> {
+        if let Some((string_idx, x)) = self.string_frets.iter().enumerate().find(|(_, x)| x.start >= value) {
+            return Err(TuningError::StartFretOutOfRange {
+                string_number: self.pitches.len() - string_idx,
+                start: x.start,
+                total_frets: value,
+            });
+        }
+        self.total_frets = value;
+        self.capo = self.capo.filter(|x| x.fret <= value);
+        Ok(())
+    }
+
+    pub fn is_behind_capo(&self, position: FretPosition) -> bool {
+        self.capo
+            .is_some_and(|capo| position.fret < capo.fret && capo.covers(position.string_idx, self.pitches.len()))
+    }
 }
 
 impl Default for Tuning {
@@ -67,7 +125,15 @@ impl Default for Tuning {
                 Pitch::new(Note::E, 4),
             ],
             total_frets: Self::DEFAULT_TOTAL_FRETS,
+            fret_spacing: FretSpacing::default(),
+            scale_length: None,
+            capo: None,
+            string_frets: Vec::new(),
+            gauges: Vec::new(),
+            fret_markers: FretMarkers::default(),
+            fret_marker_style: FretMarkerStyle::default(),
             name: String::from("Default"),
+            instrument: None,
         }
     }
 }
@@ -78,6 +144,388 @@ impl fmt::Display for Tuning {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FretSpacing {
+    #[default]
+    Even,
+    Realistic,
+}
+
+impl FretSpacing {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Even => "even",
+            Self::Realistic => "realistic",
+        }
+    }
+}
+
+impl FromStr for FretSpacing {
+    type Err = FretSpacingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "even" => Ok(Self::Even),
+            "realistic" => Ok(Self::Realistic),
+            _ => Err(FretSpacingError::from(s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FretSpacingError(String);
+
+impl From<&str> for FretSpacingError {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl fmt::Display for FretSpacingError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "unexpected fret spacing: {}, {}",
+            self.0,
+            Expected::new(&self.0, &["even", "realistic"])
+        )
+    }
+}
+
+impl error::Error for FretSpacingError {}
+
+/// The bass side refers to the first pitch of a tuning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaleLength {
+    pub bass: f32,
+    pub treble: f32,
+    /// The fret perpendicular to the strings.
+    pub neutral_fret: u8,
+}
+
+impl ScaleLength {
+    pub fn is_fanned(&self) -> bool {
+        self.bass != self.treble
+    }
+
+    pub fn get_string_length(&self, string_idx: usize, strings_count: usize) -> f32 {
+        if strings_count < 2 {
+            return self.bass;
+        }
+        let ratio = string_idx as f32 / (strings_count - 1) as f32;
+        self.bass + (self.treble - self.bass) * ratio
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Capo {
+    pub fret: u8,
+    pub strings: Option<StringRange>,
+}
+
+impl Capo {
+    pub fn covers(&self, string_idx: usize, strings_count: usize) -> bool {
+        match self.strings {
+            Some(strings) => strings.contains(strings_count - string_idx),
+            None => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StringFrets {
+    pub start: u8,
+    /// Number of frets after the start, the string goes up to the last fret of the neck when not set.
+    pub count: Option<u8>,
+}
+
+/// Diameter of a string in thousandths of an inch, e.g. 46 for the low E of a regular guitar set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StringGauge {
+    pub value: u16,
+    pub wound: bool,
+}
+
+impl StringGauge {
+    const PLAIN_MAX: u16 = 20;
+
+    pub fn new(value: u16) -> Self {
+        Self {
+            value,
+            wound: value > Self::PLAIN_MAX,
+        }
+    }
+}
+
+impl fmt::Display for StringGauge {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, "{}", self.value)?;
+        match self.wound {
+            wound if wound == Self::new(self.value).wound => Ok(()),
+            true => write!(out, "w"),
+            false => write!(out, "p"),
+        }
+    }
+}
+
+impl FromStr for StringGauge {
+    type Err = TuningError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TuningError::ParseGauge(String::from(s));
+        let (value, wound) = match s.strip_suffix('w') {
+            Some(value) => (value, Some(true)),
+            None => match s.strip_suffix('p') {
+                Some(value) => (value, Some(false)),
+                None => (s, None),
+            },
+        };
+        let value = value.parse::<u16>().ok().filter(|x| *x > 0).ok_or_else(err)?;
+        let mut result = Self::new(value);
+        if let Some(wound) = wound {
+            result.wound = wound;
+        }
+        Ok(result)
+    }
+}
+
+/// An inclusive range of string numbers, the highest pitched string is number 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StringRange {
+    pub first: usize,
+    pub last: usize,
+}
+
+impl StringRange {
+    pub fn contains(&self, string_number: usize) -> bool {
+        (self.first..=self.last).contains(&string_number)
+    }
+}
+
+impl fmt::Display for StringRange {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        if self.first == self.last {
+            write!(out, "{}", self.first)
+        } else {
+            write!(out, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+impl str::FromStr for StringRange {
+    type Err = TuningError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let err = || TuningError::ParseStringRange(String::from(value));
+        let parse = |x: &str| x.trim().parse::<usize>().ok().filter(|x| *x > 0).ok_or_else(err);
+        let (first, last) = match value.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => {
+                let number = parse(value)?;
+                (number, number)
+            }
+        };
+        Ok(Self {
+            first: first.min(last),
+            last: first.max(last),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum FretMarkers {
+    #[default]
+    Guitar,
+    Classical,
+    Mandolin,
+    Banjo,
+    None,
+    Custom(Vec<FretMarker>),
+}
+
+impl FretMarkers {
+    const PRESETS: [&str; 5] = ["guitar", "classical", "mandolin", "banjo", "none"];
+
+    pub fn get(&self, fret: u8) -> Option<FretMarkerType> {
+        let singles: &[u8] = match self {
+            Self::Guitar => &[3, 5, 7, 9],
+            Self::Classical => &[5, 7, 9],
+            Self::Mandolin => &[5, 7, 10],
+            Self::Banjo => &[3, 5, 7, 10],
+            Self::None => return None,
+            Self::Custom(items) => return items.iter().find(|x| x.fret == fret).map(|x| x.marker_type),
+        };
+        match fret.checked_sub(1)? % 12 + 1 {
+            12 => Some(FretMarkerType::Double),
+            fret if singles.contains(&fret) => Some(FretMarkerType::Single),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FretMarkers {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let items = match self {
+            Self::Guitar => return write!(out, "guitar"),
+            Self::Classical => return write!(out, "classical"),
+            Self::Mandolin => return write!(out, "mandolin"),
+            Self::Banjo => return write!(out, "banjo"),
+            Self::None => return write!(out, "none"),
+            Self::Custom(items) => items,
+        };
+        for (idx, item) in items.iter().enumerate() {
+            if idx > 0 {
+                write!(out, " ")?;
+            }
+            write!(out, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FretMarkers {
+    type Err = TuningError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guitar" => Ok(Self::Guitar),
+            "classical" => Ok(Self::Classical),
+            "mandolin" => Ok(Self::Mandolin),
+            "banjo" => Ok(Self::Banjo),
+            "none" => Ok(Self::None),
+            _ => {
+                let err = || TuningError::ParseFretMarkers(String::from(s));
+                let items: Vec<FretMarker> = s
+                    .split([' ', ','])
+                    .filter(|x| !x.is_empty())
+                    .map(|x| x.parse().map_err(|_| err()))
+                    .collect::<Result<_, _>>()?;
+                if items.is_empty() {
+                    return Err(err());
+                }
+                Ok(Self::Custom(items))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FretMarker {
+    pub fret: u8,
+    pub marker_type: FretMarkerType,
+}
+
+impl fmt::Display for FretMarker {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self.marker_type {
+            FretMarkerType::Single => write!(out, "{}", self.fret),
+            marker_type => write!(out, "{}:{}", self.fret, marker_type.count()),
+        }
+    }
+}
+
+impl FromStr for FretMarker {
+    type Err = TuningError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TuningError::ParseFretMarkers(String::from(s));
+        let (fret, count) = s.split_once(':').unwrap_or((s, "1"));
+        let fret = fret.parse::<u8>().ok().filter(|x| *x > 0).ok_or_else(err)?;
+        let marker_type = match count {
+            "1" => FretMarkerType::Single,
+            "2" => FretMarkerType::Double,
+            "3" => FretMarkerType::Triple,
+            _ => return Err(err()),
+        };
+        Ok(Self { fret, marker_type })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FretMarkerType {
+    Single,
+    Double,
+    Triple,
+}
+
+impl FretMarkerType {
+    pub fn count(self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FretMarkerStyle {
+    #[default]
+    Dot,
+    Block,
+    Trapezoid,
+    SideDot,
+}
+
+impl FretMarkerStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Block => "block",
+            Self::Trapezoid => "trapezoid",
+            Self::SideDot => "side-dot",
+        }
+    }
+}
+
+impl FromStr for FretMarkerStyle {
+    type Err = FretMarkerStyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "block" => Ok(Self::Block),
+            "trapezoid" => Ok(Self::Trapezoid),
+            "side-dot" => Ok(Self::SideDot),
+            _ => Err(FretMarkerStyleError::from(s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FretMarkerStyleError(String);
+
+impl From<&str> for FretMarkerStyleError {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl fmt::Display for FretMarkerStyleError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "unexpected fret marker style: {}, {}",
+            self.0,
+            Expected::new(&self.0, &["dot", "block", "trapezoid", "side-dot"])
+        )
+    }
+}
+
+impl error::Error for FretMarkerStyleError {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FretPosition {
+    pub string_idx: usize,
+    pub fret: u8,
+}
+
+impl FretPosition {
+    pub fn new(string_idx: usize, fret: u8) -> Self {
+        Self { string_idx, fret }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Pitch {
     pub note: Note,
@@ -85,10 +533,28 @@ pub struct Pitch {
 }
 
 impl Pitch {
-    fn new(note: Note, octave: i8) -> Self {
+    const FREQUENCY_A4: f32 = 440.0;
+    const MIDI_NUMBER_A4: i16 = 69;
+
+    pub fn new(note: Note, octave: i8) -> Self {
         Self { note, octave }
     }
 
+    pub fn get_midi_number(self) -> i16 {
+        (self.octave as i16 + 1) * 12 + self.note.index() as i16
+    }
+
+    pub fn get_frequency(self) -> f32 {
+        Self::FREQUENCY_A4 * 2f32.powf((self.get_midi_number() - Self::MIDI_NUMBER_A4) as f32 / 12.0)
+    }
+
+    pub fn transpose(self, semitones: i16) -> Option<Self> {
+        let midi_number = self.get_midi_number() + semitones;
+        let octave = i8::try_from(midi_number.div_euclid(12) - 1).ok()?;
+        let note = Note::from_index(midi_number.rem_euclid(12) as u8);
+        (-9..=9).contains(&octave).then_some(Self::new(note, octave))
+    }
+
     pub fn next(self) -> Self {
         let next_note = self.note.next();
         Self::new(
@@ -172,7 +638,7 @@ impl Iterator for PitchIter {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Note {
     A,
     Bb,
@@ -189,6 +655,21 @@ pub enum Note {
 }
 
 impl Note {
+    pub const ALL: [Note; 12] = [
+        Self::C,
+        Self::Db,
+        Self::D,
+        Self::Eb,
+        Self::E,
+        Self::F,
+        Self::Gb,
+        Self::G,
+        Self::Ab,
+        Self::A,
+        Self::Bb,
+        Self::B,
+    ];
+
     pub fn get_color(self, palette: Palette) -> iced::Color {
         match self {
             Self::A => palette.sapphire,
@@ -247,6 +728,31 @@ impl Note {
         }
     }
 
+    pub fn index(self) -> u8 {
+        match self {
+            Self::C => 0,
+            Self::Db => 1,
+            Self::D => 2,
+            Self::Eb => 3,
+            Self::E => 4,
+            Self::F => 5,
+            Self::Gb => 6,
+            Self::G => 7,
+            Self::Ab => 8,
+            Self::A => 9,
+            Self::Bb => 10,
+            Self::B => 11,
+        }
+    }
+
+    pub fn from_index(value: u8) -> Self {
+        Self::ALL[(value % 12) as usize]
+    }
+
+    pub fn transpose(self, interval: Interval) -> Self {
+        Self::from_index(self.index() + interval.semitones())
+    }
+
     pub fn next(self) -> Self {
         match self {
             Self::A => Self::Bb,
@@ -272,6 +778,15 @@ pub enum NoteFormat {
     Sharp,
 }
 
+impl NoteFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Flat => "flat",
+            Self::Sharp => "sharp",
+        }
+    }
+}
+
 impl FromStr for NoteFormat {
     type Err = NoteFormatError;
 
@@ -295,7 +810,12 @@ impl From<&str> for NoteFormatError {
 
 impl fmt::Display for NoteFormatError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
-        write!(out, "unexpected note format: {}", self.0)
+        write!(
+            out,
+            "unexpected note format: {}, {}",
+            self.0,
+            Expected::new(&self.0, &["flat", "sharp"])
+        )
     }
 }
 
@@ -305,7 +825,17 @@ impl error::Error for NoteFormatError {}
 pub enum TuningError {
     CollectionSelectEmpty,
     CollectionSelectIdx(usize),
+    ParseFretList(String),
+    ParseFretMarkers(String),
+    ParseGauge(String),
     ParsePitch(String),
+    ParseStringRange(String),
+    StartFretOutOfRange {
+        string_number: usize,
+        start: u8,
+        total_frets: u8,
+    },
+    UnknownName(String),
 }
 
 impl TuningError {
@@ -319,7 +849,37 @@ impl fmt::Display for TuningError {
         match self {
             Self::CollectionSelectEmpty => write!(out, "collection is empty"),
             Self::CollectionSelectIdx(idx) => write!(out, "invalid tuning index: {}", idx),
-            Self::ParsePitch(value) => write!(out, "parse pitch: {}", value),
+            Self::ParseFretList(value) => {
+                write!(out, "parse fret list: {}, expected numbers separated by spaces", value)
+            }
+            Self::ParseFretMarkers(value) => write!(
+                out,
+                "parse fret markers: {}, expected a preset ({}) or a list of frets, e.g. \"3 5 7 9 12:2\"",
+                value,
+                FretMarkers::PRESETS.join(", ")
+            ),
+            Self::ParseGauge(value) => write!(
+                out,
+                "parse gauge: {}, expected thousandths of an inch with an optional w (wound) or p (plain) suffix, e.g. 26w",
+                value
+            ),
+            Self::ParsePitch(value) => write!(
+                out,
+                "parse pitch: {}, expected a note name ({}) followed by an octave number, e.g. E2 or Bb-1",
+                value,
+                Note::ALL.map(|x| x.format(NoteFormat::Sharp)).join(", ")
+            ),
+            Self::ParseStringRange(value) => write!(out, "parse string range: {}", value),
+            Self::StartFretOutOfRange {
+                string_number,
+                start,
+                total_frets,
+            } => write!(
+                out,
+                "string {} starts past the last fret: {}, the tuning has {} frets",
+                string_number, start, total_frets
+            ),
+            Self::UnknownName(value) => write!(out, "unknown tuning: {}", value),
         }
     }
 }
@@ -1,28 +1,301 @@
+use std::{error, fmt, ops::RangeInclusive, str::FromStr};
+
+use super::scene::{Bounds, NoteLabel, Polygon, Scene, Segment, Shape};
 use crate::{
+    chord::{Chord, ChordFunction},
+    interval::Interval,
+    scale::{Scale, ScaleKind},
+    suggestion::Expected,
     theme::Palette,
-    tuning::{NoteFormat, Pitch, Tuning},
+    tuning::{FretMarkerStyle, FretMarkerType, FretPosition, FretSpacing, NoteFormat, Pitch, ScaleLength, Tuning},
 };
 
-#[derive(Debug)]
-pub struct Fretboard {
+pub struct Fretboard<'a, M> {
     tuning: Tuning,
     note_format: NoteFormat,
     palette: Palette,
+    scale: Option<Scale>,
+    chord: Option<Chord>,
+    hide_unhighlighted: bool,
+    label_mode: LabelMode,
+    layout_options: LayoutOptions,
+    selected: Vec<FretPosition>,
+    on_toggle: Option<Box<dyn Fn(FretPosition) -> M + 'a>>,
 }
 
-impl Fretboard {
+impl<'a, M> Fretboard<'a, M> {
     pub fn new(tuning: Tuning, note_format: NoteFormat, palette: impl Into<Palette>) -> Self {
         Self {
             tuning,
             note_format,
             palette: palette.into(),
+            scale: None,
+            chord: None,
+            hide_unhighlighted: false,
+            label_mode: LabelMode::default(),
+            layout_options: LayoutOptions::default(),
+            selected: Vec::new(),
+            on_toggle: None,
+        }
+    }
+
+    pub fn with_scale(mut self, value: Scale) -> Self {
+        self.scale = Some(value);
+        self
+    }
+
+    pub fn with_chord(mut self, value: Chord) -> Self {
+        self.chord = Some(value);
+        self
+    }
+
+    pub fn with_label_mode(mut self, value: LabelMode) -> Self {
+        self.label_mode = value;
+        self
+    }
+
+    pub fn with_hidden_unhighlighted(mut self, value: bool) -> Self {
+        self.hide_unhighlighted = value;
+        self
+    }
+
+    pub fn with_orientation(mut self, value: Orientation) -> Self {
+        self.layout_options.orientation = value;
+        self
+    }
+
+    pub fn with_left_handed(mut self, value: bool) -> Self {
+        self.layout_options.left_handed = value;
+        self
+    }
+
+    pub fn with_flipped_strings(mut self, value: bool) -> Self {
+        self.layout_options.flip_strings = value;
+        self
+    }
+
+    pub fn with_selected(mut self, value: Vec<FretPosition>) -> Self {
+        self.selected = value;
+        self
+    }
+
+    pub fn on_toggle(mut self, f: impl Fn(FretPosition) -> M + 'a) -> Self {
+        self.on_toggle = Some(Box::new(f));
+        self
+    }
+
+    pub fn get_natural_size(&self, width: f32) -> iced::Size {
+        let ratio = (self.tuning.pitches.len().max(1) as f32) / (self.tuning.total_frets.max(1) as f32);
+        let height = match self.layout_options.orientation {
+            Orientation::Auto | Orientation::Horizontal => width * ratio,
+            Orientation::Vertical => width / ratio,
+        };
+        iced::Size::new(width, height)
+    }
+
+    pub fn to_svg(&self, size: iced::Size) -> String {
+        let bounds = iced::Rectangle::new(iced::Point::ORIGIN, size);
+        let scene = self
+            .get_layout(bounds)
+            .map(|widget_layout| self.build_scene(&widget_layout))
+            .unwrap_or_default();
+        scene.to_svg(size)
+    }
+
+    fn build_scene(&self, widget_layout: &Layout) -> Scene {
+        let frets_count = self.tuning.total_frets;
+        let strings_count = self.tuning.pitches.len();
+        let capo = self.tuning.capo.filter(|x| x.fret > 0 && x.fret <= frets_count);
+        let mut scene = Scene::default();
+
+        scene.push(Bounds::new(widget_layout.bounds, self.palette.mantle));
+        scene.push(widget_layout.calculate_nut());
+        (1..=frets_count).for_each(|x| scene.push(widget_layout.calculate_fret(x)));
+        (1..=frets_count)
+            .filter_map(|fret_number| {
+                self.tuning.fret_markers.get(fret_number).map(|marker_type| {
+                    widget_layout.calculate_fret_marker(fret_number, marker_type, self.tuning.fret_marker_style)
+                })
+            })
+            .flat_map(FretMarker::into_shapes)
+            .for_each(|x| scene.push(x));
+        // Angled frets are drawn as geometry which goes on top of quads within a layer.
+        scene.push_layer();
+        for string_idx in 0..strings_count {
+            let string_number = widget_layout.cx.calculate_string_number(string_idx);
+            let frets = self.tuning.get_frets(string_idx);
+            let start = *frets.start();
+            let (thickness, wound) = self.get_string_thickness(string_idx);
+            let (string, winding) = widget_layout.calculate_string(string_number, frets, thickness, wound);
+            scene.push(string);
+            if let Some(winding) = winding {
+                scene.push(winding);
+            }
+            if start > 0 {
+                scene.push(widget_layout.calculate_string_nut(string_number, start));
+            }
+        }
+        if let Some(capo) = capo {
+            // Short strings which start past the capo are not covered.
+            let strings: Vec<usize> = (0..strings_count)
+                .filter(|x| capo.covers(*x, strings_count) && *self.tuning.get_frets(*x).start() < capo.fret)
+                .collect();
+            if let Some(segment) = widget_layout.calculate_capo(capo.fret, &strings) {
+                scene.push(segment);
+            }
+        }
+        scene.push_layer();
+        self.iter_positions()
+            .filter_map(|(position, pitch)| {
+                self.get_note_label_style(position, pitch).map(|(color, emphasis)| {
+                    let string_number = widget_layout.cx.calculate_string_number(position.string_idx);
+                    let content = self.get_note_label_content(pitch);
+                    widget_layout
+                        .note_label
+                        .calculate(position.fret, string_number, content, color, emphasis)
+                })
+            })
+            .for_each(|x| scene.push(x));
+        scene
+    }
+
+    /// The thickest string of a tuning gets the same width whatever the gauges are, others are proportional to it.
+    fn get_string_thickness(&self, string_idx: usize) -> (f32, bool) {
+        const SCALE_MAX: f32 = 2.5;
+        const SCALE_MIN: f32 = 0.5;
+        let gauge_max = self.tuning.gauges.iter().map(|x| x.value).max().unwrap_or_default();
+        match self.tuning.gauges.get(string_idx) {
+            Some(gauge) if gauge_max > 0 => (
+                (SCALE_MAX * gauge.value as f32 / gauge_max as f32).max(SCALE_MIN),
+                gauge.wound,
+            ),
+            _ => (1.0, false),
+        }
+    }
+
+    fn get_layout(&self, bounds: iced::Rectangle) -> Option<Layout> {
+        let frets_count = self.tuning.total_frets;
+        if frets_count == 0 {
+            return None;
+        }
+
+        let strings_count = self.tuning.pitches.len();
+        if strings_count == 0 {
+            return None;
+        }
+
+        if bounds.size() == iced::Size::ZERO {
+            return None;
+        }
+
+        Some(Layout::new(&self.tuning, bounds, self.palette, self.layout_options))
+    }
+
+    fn iter_positions(&self) -> impl Iterator<Item = (FretPosition, Pitch)> + '_ {
+        self.tuning
+            .pitches
+            .iter()
+            .enumerate()
+            .flat_map(move |(string_idx, pitch_origin)| {
+                self.tuning
+                    .get_frets(string_idx)
+                    .zip(*pitch_origin)
+                    .map(move |(fret_number, pitch)| (FretPosition::new(string_idx, fret_number), pitch))
+            })
+    }
+
+    fn find_position(&self, widget_layout: &Layout, point: iced::Point) -> Option<FretPosition> {
+        self.iter_positions()
+            .filter(|(position, _)| !self.tuning.is_behind_capo(*position))
+            .filter(|(position, pitch)| self.get_note_label_style(*position, *pitch).is_some())
+            .map(|(position, _)| position)
+            .find(|position| {
+                let string_number = widget_layout.cx.calculate_string_number(position.string_idx);
+                widget_layout
+                    .note_label
+                    .calculate_bounds(position.fret, string_number)
+                    .contains(point)
+            })
+    }
+
+    fn get_note_label_style(&self, position: FretPosition, pitch: Pitch) -> Option<(iced::Color, NoteLabelEmphasis)> {
+        if self.tuning.is_behind_capo(position) {
+            return Some((self.palette.overlay0, NoteLabelEmphasis::Muted));
+        }
+        if self.selected.contains(&position) {
+            return Some((self.palette.text, NoteLabelEmphasis::Selected));
+        }
+        let (color, emphasis) = if let Some(chord) = &self.chord {
+            match chord.get_function(pitch.note) {
+                Some(ChordFunction::Root) => (ChordFunction::Root.get_color(self.palette), NoteLabelEmphasis::Strong),
+                Some(function) => (function.get_color(self.palette), NoteLabelEmphasis::Normal),
+                None => (pitch.note.get_color(self.palette), NoteLabelEmphasis::Dimmed),
+            }
+        } else {
+            let emphasis = match self.scale {
+                Some(scale) if scale.kind == ScaleKind::Chromatic => NoteLabelEmphasis::Normal,
+                Some(scale) if pitch.note == scale.root => NoteLabelEmphasis::Strong,
+                Some(scale) if !scale.contains(pitch.note) => NoteLabelEmphasis::Dimmed,
+                _ => NoteLabelEmphasis::Normal,
+            };
+            (pitch.note.get_color(self.palette), emphasis)
+        };
+        match emphasis {
+            NoteLabelEmphasis::Dimmed if self.hide_unhighlighted => None,
+            emphasis => Some((color, emphasis)),
         }
     }
+
+    fn get_note_label_content(&self, pitch: Pitch) -> String {
+        let root = match (&self.chord, self.scale) {
+            (Some(chord), _) => chord.root,
+            (None, Some(scale)) => scale.root,
+            (None, None) => return self.format_pitch(pitch),
+        };
+        match self.label_mode {
+            LabelMode::Note => self.format_pitch(pitch),
+            LabelMode::Interval => String::from(Interval::between(root, pitch.note).name()),
+            LabelMode::Degree => match (&self.chord, self.scale) {
+                (None, Some(scale)) => scale.get_degree(pitch.note),
+                _ => String::from(Interval::between(root, pitch.note).degree()),
+            },
+        }
+    }
+
+    fn format_pitch(&self, pitch: Pitch) -> String {
+        format!("{}{}", pitch.note.format(self.note_format), pitch.octave)
+    }
+
+    fn get_tooltip_content(&self, position: FretPosition, pitch: Pitch) -> String {
+        let flat = pitch.note.format(NoteFormat::Flat);
+        let sharp = pitch.note.format(NoteFormat::Sharp);
+        let name = if flat == sharp {
+            format!("{}{}", sharp, pitch.octave)
+        } else {
+            format!("{}{} / {}{}", sharp, pitch.octave, flat, pitch.octave)
+        };
+        [
+            name,
+            format!("Octave {}", pitch.octave),
+            format!("MIDI {}", pitch.get_midi_number()),
+            format!("{:.2} Hz", pitch.get_frequency()),
+            format!("String {}", self.tuning.pitches.len() - position.string_idx),
+            match self.tuning.capo {
+                Some(capo)
+                    if capo.fret == position.fret && capo.covers(position.string_idx, self.tuning.pitches.len()) =>
+                {
+                    format!("Fret {} (capo)", position.fret)
+                }
+                _ => format!("Fret {}", position.fret),
+            },
+        ]
+        .join("\n")
+    }
 }
 
-impl<M, R> iced::advanced::Widget<M, iced::Theme, R> for Fretboard
+impl<M, R> iced::advanced::Widget<M, iced::Theme, R> for Fretboard<'_, M>
 where
-    R: iced::advanced::renderer::Renderer + iced::advanced::text::Renderer<Font = iced::Font>,
+    R: iced::advanced::graphics::geometry::Renderer + iced::advanced::text::Renderer<Font = iced::Font>,
 {
     fn size(&self) -> iced::Size<iced::Length> {
         let width = iced::Length::Fill;
@@ -42,9 +315,17 @@ where
         iced::advanced::layout::atomic(limits, width, height)
     }
 
+    fn tag(&self) -> iced::advanced::widget::tree::Tag {
+        iced::advanced::widget::tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> iced::advanced::widget::tree::State {
+        iced::advanced::widget::tree::State::new(State::default())
+    }
+
     fn draw(
         &self,
-        _tree: &iced::advanced::widget::Tree,
+        tree: &iced::advanced::widget::Tree,
         renderer: &mut R,
         _theme: &iced::Theme,
         _style: &iced::advanced::renderer::Style,
@@ -52,78 +333,221 @@ where
         _cursor: iced::mouse::Cursor,
         _viewport: &iced::Rectangle,
     ) {
-        let frets_count = self.tuning.total_frets;
-        if frets_count == 0 {
+        let layout_bounds = layout.bounds();
+        let Some(widget_layout) = self.get_layout(layout_bounds) else {
             return;
-        }
+        };
+        self.build_scene(&widget_layout).render(renderer, layout_bounds);
 
-        let pitches = &self.tuning.pitches;
-        let strings_count = pitches.len();
-        if strings_count == 0 {
-            return;
+        let state = tree.state.downcast_ref::<State>();
+        if let Some(position) = state.hovered
+            && let Some(pitch) = self.tuning.get_pitch(position)
+        {
+            let string_number = widget_layout.cx.calculate_string_number(position.string_idx);
+            let anchor = widget_layout.note_label.calculate_bounds(position.fret, string_number);
+            let tooltip = widget_layout.calculate_tooltip(anchor, self.get_tooltip_content(position, pitch));
+            renderer.with_layer(layout_bounds, |renderer| tooltip.render(renderer));
         }
+    }
 
-        let layout_bounds = layout.bounds();
-        let max_size = layout_bounds.size();
-        if max_size == iced::Size::ZERO {
+    fn update(
+        &mut self,
+        tree: &mut iced::advanced::widget::Tree,
+        event: &iced::Event,
+        layout: iced::advanced::layout::Layout<'_>,
+        cursor: iced::mouse::Cursor,
+        _renderer: &R,
+        _clipboard: &mut dyn iced::advanced::Clipboard,
+        shell: &mut iced::advanced::Shell<'_, M>,
+        _viewport: &iced::Rectangle,
+    ) {
+        let iced::Event::Mouse(event) = event else {
             return;
-        }
-
-        Bounds::new(layout_bounds, self.palette.mantle).render(renderer);
-
-        let widget_layout = Layout::new(
-            frets_count,
-            strings_count,
-            layout_bounds,
-            self.note_format,
-            self.palette,
-        );
-        let pitches = match widget_layout.cx.orientation {
-            Orientation::Horizontal => &mut pitches.iter().rev() as &mut dyn Iterator<Item = &Pitch>,
-            Orientation::Vertical => &mut pitches.iter() as &mut dyn Iterator<Item = &Pitch>,
         };
+        let position = cursor.position_over(layout.bounds()).and_then(|point| {
+            self.get_layout(layout.bounds())
+                .and_then(|widget_layout| self.find_position(&widget_layout, point))
+        });
+        let state = tree.state.downcast_mut::<State>();
+        if state.hovered != position {
+            state.hovered = position;
+            shell.request_redraw();
+        }
+        if let (iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left), Some(on_toggle), Some(position)) =
+            (event, &self.on_toggle, position)
+        {
+            shell.publish(on_toggle(position));
+            shell.capture_event();
+        }
+    }
 
-        widget_layout.calculate_nut().render(renderer);
-        (1..=frets_count)
-            .map(|x| widget_layout.calculate_fret(x))
-            .for_each(|x| x.render(renderer));
-        (1..=frets_count)
-            .zip(FretMarkerType::MARKUP.into_iter().cycle())
-            .filter_map(|(fret_number, marker_type)| {
-                marker_type.map(|marker_type| widget_layout.calculate_fret_marker(fret_number, marker_type))
-            })
-            .for_each(|x| x.render(renderer));
-        (1..=strings_count)
-            .map(|x| widget_layout.calculate_string(x))
-            .for_each(|x| x.render(renderer));
-        pitches
-            .enumerate()
-            .flat_map(|(pitch_number, pitch_origin)| {
-                let string_number = pitch_number + 1;
-                (0..=frets_count).zip(*pitch_origin).map(move |(fret_number, pitch)| {
-                    widget_layout.note_label.calculate(fret_number, string_number, pitch)
-                })
-            })
-            .for_each(move |note_label| note_label.render(renderer));
+    fn mouse_interaction(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        _layout: iced::advanced::layout::Layout<'_>,
+        _cursor: iced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+        _renderer: &R,
+    ) -> iced::mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        match (&self.on_toggle, state.hovered) {
+            (Some(_), Some(_)) => iced::mouse::Interaction::Pointer,
+            _ => iced::mouse::Interaction::None,
+        }
     }
 }
 
-impl<M, R> From<Fretboard> for iced::Element<'_, M, iced::Theme, R>
+impl<'a, M, R> From<Fretboard<'a, M>> for iced::Element<'a, M, iced::Theme, R>
 where
-    R: iced::advanced::text::Renderer<Font = iced::Font>,
+    M: 'a,
+    R: iced::advanced::graphics::geometry::Renderer + iced::advanced::text::Renderer<Font = iced::Font> + 'a,
 {
-    fn from(value: Fretboard) -> Self {
+    fn from(value: Fretboard<'a, M>) -> Self {
         Self::new(value)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Orientation {
+#[derive(Debug, Default)]
+struct State {
+    hovered: Option<FretPosition>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LabelMode {
+    Degree,
+    Interval,
+    #[default]
+    Note,
+}
+
+impl LabelMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Degree => "degree",
+            Self::Interval => "interval",
+            Self::Note => "note",
+        }
+    }
+}
+
+impl FromStr for LabelMode {
+    type Err = LabelModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "degree" => Ok(Self::Degree),
+            "interval" => Ok(Self::Interval),
+            "note" => Ok(Self::Note),
+            _ => Err(LabelModeError::from(s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LabelModeError(String);
+
+impl From<&str> for LabelModeError {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl fmt::Display for LabelModeError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "unexpected label mode: {}, {}",
+            self.0,
+            Expected::new(&self.0, &["degree", "interval", "note"])
+        )
+    }
+}
+
+impl error::Error for LabelModeError {}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Orientation {
+    #[default]
+    Auto,
     Horizontal,
     Vertical,
 }
 
 impl Orientation {
+    pub const ALL: [Orientation; 3] = [Self::Auto, Self::Horizontal, Self::Vertical];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Horizontal => "horizontal",
+            Self::Vertical => "vertical",
+        }
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "{}",
+            match self {
+                Self::Auto => "Auto",
+                Self::Horizontal => "Horizontal",
+                Self::Vertical => "Vertical",
+            }
+        )
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = OrientationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "horizontal" => Ok(Self::Horizontal),
+            "vertical" => Ok(Self::Vertical),
+            _ => Err(OrientationError::from(s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OrientationError(String);
+
+impl From<&str> for OrientationError {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl fmt::Display for OrientationError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "unexpected orientation: {}, {}",
+            self.0,
+            Expected::new(&self.0, &["auto", "horizontal", "vertical"])
+        )
+    }
+}
+
+impl error::Error for OrientationError {}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct LayoutOptions {
+    flip_strings: bool,
+    left_handed: bool,
+    orientation: Orientation,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
     fn transform_point(&self, value: iced::Point) -> iced::Point {
         match self {
             Self::Horizontal => value,
@@ -142,19 +566,25 @@ impl Orientation {
 struct Cx {
     note_label_bounds_width: f32,
     note_label_font_size: f32,
-    orientation: Orientation,
+    axis: Axis,
+    flip_strings: bool,
+    left_handed: bool,
     origin: iced::Point,
     origin_fret: f32,
-    origin_fret_marker_double_a: f32,
-    origin_fret_marker_double_b: f32,
-    origin_fret_marker_single: f32,
+    origin_fret_marker: f32,
     origin_nut: f32,
     size_fret: iced::Size,
     size_fret_marker: iced::Size,
     size_nut: iced::Size,
     size_string: iced::Size,
+    fan: Option<Fan>,
+    fret_spacing: FretSpacing,
+    frets_count: u8,
+    length_frets: f32,
+    length_pitches: f32,
     spacing_fret: f32,
     spacing_string: f32,
+    strings_count: usize,
 }
 
 impl Cx {
@@ -165,19 +595,23 @@ impl Cx {
     const SCALE_NUT: f32 = 0.002;
     const SCALE_STRING: f32 = 0.005;
 
-    fn new(frets_count: u8, strings_count: usize, bounds: iced::Rectangle) -> Self {
-        let origin = bounds.position();
-        let max_size = bounds.size();
-        let frets_count = frets_count as f32;
-        let strings_count = strings_count as f32;
+    fn new(tuning: &Tuning, bounds: iced::Rectangle, options: LayoutOptions) -> Self {
+        let total_frets = tuning.total_frets;
+        let total_strings = tuning.pitches.len();
+        let frets_count = total_frets as f32;
+        let strings_count = total_strings as f32;
 
-        let (width_frets, width_pitches, orientation) = if max_size.width > max_size.height {
-            (max_size.width, max_size.height, Orientation::Horizontal)
-        } else {
-            (max_size.height, max_size.width, Orientation::Vertical)
+        let axis = match options.orientation {
+            Orientation::Auto if bounds.width > bounds.height => Axis::Horizontal,
+            Orientation::Auto | Orientation::Vertical => Axis::Vertical,
+            Orientation::Horizontal => Axis::Horizontal,
         };
+        // Calculations are done in the horizontal space, points are transformed when a shape is created.
+        let origin = axis.transform_point(bounds.position());
+        let max_size = axis.transform_size(bounds.size());
+        let (width_frets, width_pitches) = (max_size.width, max_size.height);
         let ratio = frets_count / strings_count;
-        let length_frets = (width_frets / frets_count) * frets_count;
+        let length_frets = width_frets.min(width_pitches * ratio);
         let length_pitches = length_frets / ratio;
 
         let origin = iced::Point::new(
@@ -193,40 +627,140 @@ impl Cx {
         let origin_nut = origin.x + note_label_bounds_width * 1.25;
         let origin_fret = origin_nut + nut_width;
 
-        let spacing_fret = (length_frets - origin_fret) / (frets_count + 1.0);
+        let spacing_fret = (origin.x + length_frets - origin_fret) / (frets_count + 1.0);
         let spacing_string = length_pitches / (strings_count + 1.0);
 
         let fret_marker_width = spacing_fret * Self::SCALE_FRET_MARKER;
-        let origin_fret_marker_single = origin.y + ((length_pitches / 2.0) - (fret_marker_width / 2.0));
+
+        let fan = tuning
+            .scale_length
+            .filter(|x| x.is_fanned() && total_strings > 1)
+            .map(|scale_length| {
+                let calculate_string_position_y = |string_idx: usize| {
+                    let number = Self::get_string_number(axis, options.flip_strings, total_strings, string_idx);
+                    spacing_string * number as f32 + origin.y
+                };
+                Fan::new(
+                    scale_length,
+                    total_frets,
+                    calculate_string_position_y(0),
+                    calculate_string_position_y(total_strings - 1),
+                    (origin.y, origin.y + length_pitches),
+                    (origin_fret, spacing_fret * (frets_count + 1.0)),
+                )
+            });
 
         Self {
             note_label_bounds_width,
             note_label_font_size,
-            orientation,
+            axis,
+            flip_strings: options.flip_strings,
+            left_handed: options.left_handed,
             origin,
             origin_fret,
-            origin_fret_marker_double_a: origin_fret_marker_single - fret_marker_width,
-            origin_fret_marker_double_b: origin_fret_marker_single + fret_marker_width,
-            origin_fret_marker_single,
+            origin_fret_marker: origin.y + length_pitches / 2.0,
             origin_nut,
-            size_nut: orientation.transform_size(iced::Size::new(nut_width, length_pitches)),
-            size_fret: orientation.transform_size(iced::Size::new(length_frets * Self::SCALE_FRET, length_pitches)),
+            size_nut: iced::Size::new(nut_width, length_pitches),
+            size_fret: iced::Size::new(length_frets * Self::SCALE_FRET, length_pitches),
             size_fret_marker: iced::Size::new(fret_marker_width, fret_marker_width),
-            size_string: orientation.transform_size(iced::Size::new(
-                length_frets - origin_nut,
+            size_string: iced::Size::new(
+                origin.x + length_frets - origin_nut,
                 length_pitches * Self::SCALE_STRING,
-            )),
+            ),
+            length_frets,
+            fan,
+            fret_spacing: tuning.fret_spacing,
+            frets_count: total_frets,
+            length_pitches,
             spacing_fret,
             spacing_string,
+            strings_count: total_strings,
         }
     }
 
     fn calculate_fret_position_x(&self, number: u8) -> f32 {
-        self.spacing_fret * number as f32 + self.origin_fret
+        match self.fret_spacing {
+            FretSpacing::Even => self.spacing_fret * number as f32 + self.origin_fret,
+            FretSpacing::Realistic => {
+                // The scale length is chosen so that the last fret lands at the same place as with even spacing.
+                let length = self.spacing_fret * (self.frets_count as f32 + 1.0);
+                let scale_length = length / Self::calculate_fret_distance(self.frets_count as f32 + 1.0);
+                self.origin_fret + scale_length * Self::calculate_fret_distance(number as f32)
+            }
+        }
+    }
+
+    fn calculate_fret_position_x_at(&self, number: u8, y: f32) -> f32 {
+        match self.fan {
+            Some(fan) => fan.calculate_fret_position_x(number, y),
+            None => self.calculate_fret_position_x(number),
+        }
+    }
+
+    fn calculate_fret_midpoint_x_at(&self, fret_number: u8, y: f32) -> f32 {
+        (self.calculate_fret_position_x_at(fret_number.saturating_sub(1), y)
+            + self.calculate_fret_position_x_at(fret_number, y))
+            / 2.0
     }
 
     fn calculate_fret_marker_position_x(&self, fret_number: u8) -> f32 {
-        self.calculate_fret_position_x(fret_number) - (self.spacing_fret / 2.0)
+        self.calculate_fret_midpoint_x_at(fret_number, self.origin.y + self.length_pitches / 2.0)
+    }
+
+    fn calculate_note_label_position_x(&self, fret_number: u8, y: f32, padding: f32) -> f32 {
+        let is_realistic = self.fan.is_some() || self.fret_spacing == FretSpacing::Realistic;
+        if is_realistic && fret_number > 0 {
+            self.calculate_fret_midpoint_x_at(fret_number, y)
+        } else {
+            self.calculate_fret_position_x_at(fret_number, y) - padding
+        }
+    }
+
+    /// Returns the distance from the nut to a fret relative to the scale length: 1 - 2^(-n/12).
+    fn calculate_fret_distance(number: f32) -> f32 {
+        1.0 - 2f32.powf(-number / 12.0)
+    }
+
+    fn calculate_string_number(&self, string_idx: usize) -> usize {
+        Self::get_string_number(self.axis, self.flip_strings, self.strings_count, string_idx)
+    }
+
+    /// The first pitch goes at the bottom in horizontal layout and on the left in vertical layout unless flipped.
+    fn get_string_number(axis: Axis, flip_strings: bool, strings_count: usize, string_idx: usize) -> usize {
+        let string_idx = if flip_strings {
+            strings_count - 1 - string_idx
+        } else {
+            string_idx
+        };
+        match axis {
+            Axis::Horizontal => strings_count - string_idx,
+            Axis::Vertical => string_idx + 1,
+        }
+    }
+
+    fn transform_point(&self, value: iced::Point) -> iced::Point {
+        self.axis
+            .transform_point(iced::Point::new(self.mirror_x(value.x), value.y))
+    }
+
+    fn transform_rectangle(&self, origin: iced::Point, size: iced::Size) -> iced::Rectangle {
+        let x = if self.left_handed {
+            self.mirror_x(origin.x + size.width)
+        } else {
+            origin.x
+        };
+        iced::Rectangle::new(
+            self.axis.transform_point(iced::Point::new(x, origin.y)),
+            self.axis.transform_size(size),
+        )
+    }
+
+    fn mirror_x(&self, x: f32) -> f32 {
+        if self.left_handed {
+            2.0 * self.origin.x + self.length_frets - x
+        } else {
+            x
+        }
     }
 
     fn calculate_string_position_y(&self, number: usize) -> f32 {
@@ -234,118 +768,353 @@ impl Cx {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+struct Fan {
+    length_bass: f32,
+    length_treble: f32,
+    neutral_distance: f32,
+    origin_bass: f32,
+    origin_neutral: f32,
+    origin_treble: f32,
+    scale: f32,
+}
+
+impl Fan {
+    fn new(
+        scale_length: ScaleLength,
+        frets_count: u8,
+        origin_bass: f32,
+        origin_treble: f32,
+        edges: (f32, f32),
+        (origin_fret, length_frets): (f32, f32),
+    ) -> Self {
+        let mut result = Self {
+            length_bass: scale_length.bass,
+            length_treble: scale_length.treble,
+            neutral_distance: Cx::calculate_fret_distance(scale_length.neutral_fret.min(frets_count) as f32),
+            origin_bass,
+            origin_neutral: 0.0,
+            origin_treble,
+            scale: 1.0,
+        };
+        let length_max = result
+            .calculate_string_length(edges.0)
+            .max(result.calculate_string_length(edges.1));
+        result.scale = length_frets / (length_max * Cx::calculate_fret_distance(frets_count as f32 + 1.0));
+        result.origin_neutral = origin_fret + length_max * result.scale * result.neutral_distance;
+        result
+    }
+
+    fn calculate_string_length(&self, y: f32) -> f32 {
+        let ratio = (y - self.origin_bass) / (self.origin_treble - self.origin_bass);
+        (self.length_bass + (self.length_treble - self.length_bass) * ratio).max(0.0)
+    }
+
+    fn calculate_fret_position_x(&self, number: u8, y: f32) -> f32 {
+        let length = self.calculate_string_length(y) * self.scale;
+        self.origin_neutral + length * (Cx::calculate_fret_distance(number as f32) - self.neutral_distance)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Layout {
+    bounds: iced::Rectangle,
     note_label: LayoutNoteLabel,
     cx: Cx,
     palette: Palette,
 }
 
 impl Layout {
-    fn new(
-        frets_count: u8,
-        strings_count: usize,
-        bounds: iced::Rectangle,
-        note_format: NoteFormat,
-        palette: Palette,
-    ) -> Self {
-        let cx = Cx::new(frets_count, strings_count, bounds);
+    fn new(tuning: &Tuning, bounds: iced::Rectangle, palette: Palette, options: LayoutOptions) -> Self {
+        let cx = Cx::new(tuning, bounds, options);
         Self {
+            bounds,
             cx,
-            note_label: LayoutNoteLabel::new(cx, note_format, palette),
+            note_label: LayoutNoteLabel::new(cx, palette),
             palette,
         }
     }
 
-    fn calculate_nut(&self) -> Bounds {
-        let origin = self
-            .cx
-            .orientation
-            .transform_point(iced::Point::new(self.cx.origin_nut, self.cx.origin.y));
-        Bounds::new(iced::Rectangle::new(origin, self.cx.size_nut), self.palette.peach)
+    fn calculate_nut(&self) -> FretLine {
+        if self.cx.fan.is_some() {
+            let width = self.cx.size_nut.width;
+            return self.calculate_fret_line_angled(0, -width / 2.0, width, self.palette.peach);
+        }
+        let origin = iced::Point::new(self.cx.origin_nut, self.cx.origin.y);
+        FretLine::Straight(Bounds::new(
+            self.cx.transform_rectangle(origin, self.cx.size_nut),
+            self.palette.peach,
+        ))
     }
 
-    fn calculate_fret(&self, fret_number: u8) -> Bounds {
+    fn calculate_fret(&self, fret_number: u8) -> FretLine {
+        if self.cx.fan.is_some() {
+            let width = self.cx.size_fret.width;
+            return self.calculate_fret_line_angled(fret_number, 0.0, width, self.palette.overlay0);
+        }
         let x = self.cx.calculate_fret_position_x(fret_number);
-        let point = self
+        let bounds = self
             .cx
-            .orientation
-            .transform_point(iced::Point::new(x, self.cx.origin.y));
-        let bounds = iced::Rectangle::new(point, self.cx.size_fret);
-        Bounds::new(bounds, self.palette.overlay0)
-    }
-
-    fn calculate_fret_marker(&self, fret_number: u8, marker_type: FretMarkerType) -> FretMarker {
-        let x = self.cx.calculate_fret_marker_position_x(fret_number);
-        let orientation = self.cx.orientation;
-        let pos = |y: f32| orientation.transform_point(iced::Point::new(x, y));
-        match marker_type {
-            FretMarkerType::Single => FretMarker::Single(Bounds::new(
-                iced::Rectangle::new(pos(self.cx.origin_fret_marker_single), self.cx.size_fret_marker),
-                self.palette.text,
-            )),
-            FretMarkerType::Double => FretMarker::Double(
-                Bounds::new(
-                    iced::Rectangle::new(pos(self.cx.origin_fret_marker_double_a), self.cx.size_fret_marker),
-                    self.palette.text,
-                ),
-                Bounds::new(
-                    iced::Rectangle::new(pos(self.cx.origin_fret_marker_double_b), self.cx.size_fret_marker),
-                    self.palette.text,
-                ),
-            ),
+            .transform_rectangle(iced::Point::new(x, self.cx.origin.y), self.cx.size_fret);
+        FretLine::Straight(Bounds::new(bounds, self.palette.overlay0))
+    }
+
+    fn calculate_fret_line_angled(&self, fret_number: u8, offset: f32, width: f32, color: iced::Color) -> FretLine {
+        let point = |y: f32| {
+            let x = self.cx.calculate_fret_position_x_at(fret_number, y) + offset + width / 2.0;
+            self.cx.transform_point(iced::Point::new(x, y))
+        };
+        FretLine::Angled(Segment {
+            from: point(self.cx.origin.y),
+            to: point(self.cx.origin.y + self.cx.length_pitches),
+            width,
+            color,
+            dash: None,
+            bounds: self.bounds,
+        })
+    }
+
+    fn calculate_fret_marker(
+        &self,
+        fret_number: u8,
+        marker_type: FretMarkerType,
+        style: FretMarkerStyle,
+    ) -> FretMarker {
+        let count = marker_type.count();
+        let offsets = (0..count).map(move |idx| idx as f32 - (count - 1) as f32 / 2.0);
+        match style {
+            FretMarkerStyle::Dot => {
+                let x = self.cx.calculate_fret_marker_position_x(fret_number);
+                let size = self.cx.size_fret_marker;
+                let dots = offsets
+                    .map(|offset| {
+                        let y = self.cx.origin_fret_marker + offset * size.height * 2.0;
+                        self.calculate_fret_marker_dot(iced::Point::new(x, y), size)
+                    })
+                    .collect();
+                FretMarker::Dots(dots)
+            }
+            FretMarkerStyle::SideDot => {
+                let size = self.cx.size_fret_marker;
+                let y_bass = self.cx.calculate_string_position_y(self.cx.calculate_string_number(0));
+                let y_edge = if y_bass < self.cx.origin_fret_marker {
+                    self.cx.origin.y
+                } else {
+                    self.cx.origin.y + self.cx.length_pitches
+                };
+                let y = (y_bass + y_edge) / 2.0;
+                let x = self.cx.calculate_fret_midpoint_x_at(fret_number, y);
+                let dots = offsets
+                    .map(|offset| {
+                        let x = x + offset * size.width * 2.0;
+                        self.calculate_fret_marker_dot(iced::Point::new(x, y), size)
+                    })
+                    .collect();
+                FretMarker::Dots(dots)
+            }
+            FretMarkerStyle::Block | FretMarkerStyle::Trapezoid => {
+                let inlays = offsets
+                    .map(|offset| self.calculate_fret_marker_inlay(fret_number, offset, count, style))
+                    .collect();
+                FretMarker::Inlays(inlays)
+            }
+        }
+    }
+
+    fn calculate_fret_marker_dot(&self, center: iced::Point, size: iced::Size) -> Bounds {
+        let origin = iced::Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0);
+        Bounds::new(self.cx.transform_rectangle(origin, size), self.palette.text).with_border(iced::Border {
+            radius: iced::border::Radius::new(size.width / 2.0),
+            ..iced::Border::default()
+        })
+    }
+
+    fn calculate_fret_marker_inlay(
+        &self,
+        fret_number: u8,
+        offset: f32,
+        count: usize,
+        style: FretMarkerStyle,
+    ) -> Polygon {
+        const SCALE_EDGE: f32 = 0.15;
+        const SCALE_GAP: f32 = 0.06;
+        const SCALE_INSET: f32 = 0.2;
+        const SCALE_INSET_NARROW: f32 = 0.3;
+        let length = self.cx.length_pitches * (1.0 - SCALE_EDGE * 2.0);
+        let gap = self.cx.length_pitches * SCALE_GAP;
+        let piece = (length - gap * (count - 1) as f32) / count as f32;
+        let y_a = self.cx.origin_fret_marker + offset * (piece + gap) - piece / 2.0;
+        let y_b = y_a + piece;
+        let inset_a = match style {
+            FretMarkerStyle::Trapezoid => SCALE_INSET_NARROW,
+            _ => SCALE_INSET,
+        };
+        let point = |y: f32, inset: f32, is_end: bool| {
+            let from = self.cx.calculate_fret_position_x_at(fret_number - 1, y);
+            let to = self.cx.calculate_fret_position_x_at(fret_number, y);
+            let distance = (to - from) * inset;
+            let x = if is_end { to - distance } else { from + distance };
+            self.cx.transform_point(iced::Point::new(x, y))
+        };
+        Polygon {
+            bounds: self.bounds,
+            color: self.palette.surface2,
+            points: vec![
+                point(y_a, inset_a, false),
+                point(y_a, inset_a, true),
+                point(y_b, SCALE_INSET, true),
+                point(y_b, SCALE_INSET, false),
+            ],
+        }
+    }
+
+    fn calculate_tooltip(&self, anchor: iced::Rectangle, content: String) -> Tooltip {
+        const CHAR_WIDTH: f32 = 0.6;
+        const SCALE_PADDING: f32 = 0.75;
+        let font_size = self.cx.note_label_font_size;
+        let padding = font_size * SCALE_PADDING;
+        let lines = content.lines().count() as f32;
+        let columns = content.lines().map(|x| x.chars().count()).max().unwrap_or_default() as f32;
+        let text_size = iced::Size::new(
+            columns * font_size * CHAR_WIDTH,
+            lines * font_size * Tooltip::LINE_HEIGHT,
+        );
+        let size = iced::Size::new(text_size.width + padding * 2.0, text_size.height + padding * 2.0);
+        let mut origin = iced::Point::new(anchor.x + anchor.width + padding, anchor.y + anchor.height + padding);
+        if origin.x + size.width > self.bounds.x + self.bounds.width {
+            origin.x = anchor.x - padding - size.width;
+        }
+        if origin.y + size.height > self.bounds.y + self.bounds.height {
+            origin.y = anchor.y - padding - size.height;
+        }
+        origin.x = origin.x.max(self.bounds.x);
+        origin.y = origin.y.max(self.bounds.y);
+        Tooltip {
+            bounds: Bounds::new(iced::Rectangle::new(origin, size), self.palette.surface0).with_border(iced::Border {
+                color: self.palette.overlay0,
+                width: 1.0,
+                radius: iced::border::Radius::new(padding),
+            }),
+            location: iced::Point::new(origin.x + padding, origin.y + padding),
+            text: iced::advanced::text::Text {
+                bounds: text_size,
+                content,
+                font: iced::Font::MONOSPACE,
+                align_x: iced::advanced::text::Alignment::Left,
+                line_height: iced::advanced::text::LineHeight::Relative(Tooltip::LINE_HEIGHT),
+                shaping: iced::advanced::text::Shaping::Advanced,
+                size: iced::Pixels::from(font_size),
+                align_y: iced::alignment::Vertical::Top,
+                wrapping: iced::advanced::text::Wrapping::None,
+            },
+            text_color: self.palette.text,
         }
     }
 
-    fn calculate_string(&self, string_number: usize) -> Bounds {
+    fn calculate_capo(&self, fret: u8, strings: &[usize]) -> Option<Segment> {
+        const SCALE_OFFSET: f32 = 0.3;
+        const SCALE_WIDTH: f32 = 0.5;
+        let mut positions = strings
+            .iter()
+            .map(|x| self.cx.calculate_string_position_y(self.cx.calculate_string_number(*x)));
+        let first = positions.next()?;
+        let (y_min, y_max) = positions.fold((first, first), |(min, max), y| (min.min(y), max.max(y)));
+        let overhang = self.cx.spacing_string / 2.0;
+        let point = |y: f32| {
+            let x = self.cx.calculate_fret_position_x_at(fret, y);
+            let gap = x - self.cx.calculate_fret_position_x_at(fret - 1, y);
+            self.cx.transform_point(iced::Point::new(x - gap * SCALE_OFFSET, y))
+        };
+        Some(Segment {
+            from: point(y_min - overhang),
+            to: point(y_max + overhang),
+            width: self.cx.note_label_bounds_width * SCALE_WIDTH,
+            color: self.palette.subtext0,
+            dash: None,
+            bounds: self.bounds,
+        })
+    }
+
+    fn calculate_string(
+        &self,
+        string_number: usize,
+        frets: RangeInclusive<u8>,
+        thickness: f32,
+        wound: bool,
+    ) -> (Bounds, Option<Segment>) {
+        const SCALE_WINDING_STEP: f32 = 1.5;
+        const SCALE_WINDING_WIDTH: f32 = 0.5;
+        const WINDING_ALPHA: f32 = 0.5;
+        let (start_fret, end_fret) = frets.into_inner();
         let y = self.cx.calculate_string_position_y(string_number);
-        let point = self
-            .cx
-            .orientation
-            .transform_point(iced::Point::new(self.cx.origin_nut, y));
-        let bounds = iced::Rectangle::new(point, self.cx.size_string);
-        Bounds::new(bounds, self.palette.lavender)
+        let x = if start_fret > 0 || self.cx.fan.is_some() {
+            self.cx.calculate_fret_position_x_at(start_fret, y)
+        } else {
+            self.cx.origin_nut
+        };
+        let x_end = if end_fret < self.cx.frets_count {
+            self.cx.calculate_fret_position_x_at(end_fret, y)
+        } else {
+            self.cx.origin_nut + self.cx.size_string.width
+        };
+        let size = iced::Size::new(x_end - x, self.cx.size_string.height * thickness);
+        let origin = iced::Point::new(x, y - size.height / 2.0);
+        let string = Bounds::new(self.cx.transform_rectangle(origin, size), self.palette.lavender);
+        let winding = wound.then(|| {
+            let dash = size.height * SCALE_WINDING_WIDTH;
+            let gap = size.height * SCALE_WINDING_STEP - dash;
+            Segment {
+                from: self.cx.transform_point(iced::Point::new(x, y)),
+                to: self.cx.transform_point(iced::Point::new(x_end, y)),
+                width: size.height,
+                color: iced::Color {
+                    a: WINDING_ALPHA,
+                    ..self.palette.crust
+                },
+                dash: Some([dash, gap]),
+                bounds: self.bounds,
+            }
+        });
+        (string, winding)
+    }
+
+    fn calculate_string_nut(&self, string_number: usize, fret: u8) -> Bounds {
+        const SCALE_HEIGHT: f32 = 0.4;
+        let y = self.cx.calculate_string_position_y(string_number);
+        let height = self.cx.spacing_string * SCALE_HEIGHT;
+        let x = self.cx.calculate_fret_position_x_at(fret, y);
+        let size = iced::Size::new(self.cx.size_nut.width * 2.0, height);
+        let bounds = self.cx.transform_rectangle(iced::Point::new(x, y - height / 2.0), size);
+        Bounds::new(bounds, self.palette.peach)
     }
 }
 
 #[derive(Clone, Copy, Debug)]
-enum FretMarkerType {
-    Double,
-    Single,
-}
-
-impl FretMarkerType {
-    const MARKUP: [Option<FretMarkerType>; 12] = [
-        None,
-        None,
-        Some(Self::Single),
-        None,
-        Some(Self::Single),
-        None,
-        Some(Self::Single),
-        None,
-        Some(Self::Single),
-        None,
-        None,
-        Some(Self::Double),
-    ];
+enum FretLine {
+    Angled(Segment),
+    Straight(Bounds),
 }
 
-#[derive(Clone, Copy, Debug)]
-#[allow(clippy::large_enum_variant)]
+impl From<FretLine> for Shape {
+    fn from(value: FretLine) -> Self {
+        match value {
+            FretLine::Angled(segment) => segment.into(),
+            FretLine::Straight(bounds) => bounds.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 enum FretMarker {
-    Single(Bounds),
-    Double(Bounds, Bounds),
+    Dots(Vec<Bounds>),
+    Inlays(Vec<Polygon>),
 }
 
 impl FretMarker {
-    fn render(self, renderer: &mut impl iced::advanced::Renderer) {
+    fn into_shapes(self) -> Vec<Shape> {
         match self {
-            FretMarker::Single(bounds) => bounds.render(renderer),
-            FretMarker::Double(bounds_a, bounds_b) => {
-                bounds_a.render(renderer);
-                bounds_b.render(renderer);
-            }
+            FretMarker::Dots(items) => items.into_iter().map(Shape::from).collect(),
+            FretMarker::Inlays(items) => items.into_iter().map(Shape::from).collect(),
         }
     }
 }
@@ -356,7 +1125,6 @@ struct LayoutNoteLabel {
     clip_border: iced::Border,
     cx: Cx,
     font_size: iced::Pixels,
-    note_format: NoteFormat,
     padding: f32,
     palette: Palette,
 }
@@ -364,6 +1132,9 @@ struct LayoutNoteLabel {
 impl LayoutNoteLabel {
     const BORDER_RADIUS: f32 = 0.5;
     const BORDER_WIDTH: f32 = 1.0;
+    const BORDER_WIDTH_STRONG: f32 = 3.0;
+    const DIMMED_ALPHA: f32 = 0.2;
+    const MUTED_ALPHA: f32 = 0.5;
     const FONT: iced::Font = iced::Font::MONOSPACE;
     const SCALE_PADDING: f32 = 1.25;
     const TEXT_ALIGN_H: iced::advanced::text::Alignment = iced::advanced::text::Alignment::Center;
@@ -372,7 +1143,7 @@ impl LayoutNoteLabel {
     const TEXT_SHAPING: iced::advanced::text::Shaping = iced::advanced::text::Shaping::Advanced;
     const TEXT_WRAPPING: iced::advanced::text::Wrapping = iced::advanced::text::Wrapping::None;
 
-    fn new(cx: Cx, note_format: NoteFormat, palette: Palette) -> Self {
+    fn new(cx: Cx, palette: Palette) -> Self {
         let bounds_width = cx.note_label_bounds_width;
         let clip_border = iced::Border {
             color: palette.base,
@@ -384,30 +1155,75 @@ impl LayoutNoteLabel {
             clip_border,
             cx,
             font_size: iced::Pixels::from(cx.note_label_font_size),
-            note_format,
             padding: cx.note_label_font_size * Self::SCALE_PADDING,
             palette,
         }
     }
 
-    fn calculate(&self, fret_number: u8, string_number: usize, pitch: Pitch) -> NoteLabel {
-        let x = self.cx.calculate_fret_position_x(fret_number) - self.padding;
+    fn calculate_location(&self, fret_number: u8, string_number: usize) -> iced::Point {
         let y = self.cx.calculate_string_position_y(string_number);
-        let location = self.cx.orientation.transform_point(iced::Point::new(x, y));
-        let clip_bounds = Bounds::new(
-            iced::Rectangle::new(
-                iced::Point::new(location.x - self.padding, location.y - self.padding),
-                self.bounds_size,
-            ),
-            pitch.note.get_color(self.palette),
+        let x = self.cx.calculate_note_label_position_x(fret_number, y, self.padding);
+        self.cx.transform_point(iced::Point::new(x, y))
+    }
+
+    fn calculate_bounds(&self, fret_number: u8, string_number: usize) -> iced::Rectangle {
+        self.calculate_bounds_at(self.calculate_location(fret_number, string_number))
+    }
+
+    fn calculate_bounds_at(&self, location: iced::Point) -> iced::Rectangle {
+        iced::Rectangle::new(
+            iced::Point::new(location.x - self.padding, location.y - self.padding),
+            self.bounds_size,
         )
-        .with_border(self.clip_border);
+    }
+
+    fn calculate(
+        &self,
+        fret_number: u8,
+        string_number: usize,
+        content: String,
+        color: iced::Color,
+        emphasis: NoteLabelEmphasis,
+    ) -> NoteLabel {
+        let location = self.calculate_location(fret_number, string_number);
+        let (color, text_color, clip_border) = match emphasis {
+            NoteLabelEmphasis::Normal => (color, self.palette.crust, self.clip_border),
+            NoteLabelEmphasis::Strong => (
+                color,
+                self.palette.crust,
+                iced::Border {
+                    color: self.palette.text,
+                    width: Self::BORDER_WIDTH_STRONG,
+                    ..self.clip_border
+                },
+            ),
+            NoteLabelEmphasis::Dimmed => (
+                color.scale_alpha(Self::DIMMED_ALPHA),
+                self.palette.crust.scale_alpha(Self::DIMMED_ALPHA),
+                self.clip_border,
+            ),
+            NoteLabelEmphasis::Muted => (
+                color.scale_alpha(Self::MUTED_ALPHA),
+                self.palette.text.scale_alpha(Self::MUTED_ALPHA),
+                self.clip_border,
+            ),
+            NoteLabelEmphasis::Selected => (
+                color,
+                self.palette.base,
+                iced::Border {
+                    color: self.palette.rosewater,
+                    width: Self::BORDER_WIDTH_STRONG,
+                    ..self.clip_border
+                },
+            ),
+        };
+        let clip_bounds = Bounds::new(self.calculate_bounds_at(location), color).with_border(clip_border);
         NoteLabel {
             clip_bounds,
             location,
             text: iced::advanced::text::Text {
                 bounds: self.bounds_size,
-                content: format!("{}{}", pitch.note.format(self.note_format), pitch.octave),
+                content,
                 font: Self::FONT,
                 align_x: Self::TEXT_ALIGN_H,
                 line_height: Self::TEXT_LINE_HEIGHT,
@@ -416,51 +1232,74 @@ impl LayoutNoteLabel {
                 align_y: Self::TEXT_ALIGN_V,
                 wrapping: Self::TEXT_WRAPPING,
             },
-            text_color: self.palette.crust,
+            text_color,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+enum NoteLabelEmphasis {
+    Dimmed,
+    Muted,
+    Normal,
+    Selected,
+    Strong,
+}
+
 #[derive(Debug)]
-struct NoteLabel {
-    clip_bounds: Bounds,
+struct Tooltip {
+    bounds: Bounds,
     location: iced::Point,
     text: iced::advanced::Text,
     text_color: iced::Color,
 }
 
-impl NoteLabel {
+impl Tooltip {
+    const LINE_HEIGHT: f32 = 1.3;
+
     fn render(self, renderer: &mut impl iced::advanced::text::Renderer<Font = iced::Font>) {
-        self.clip_bounds.render(renderer);
-        renderer.fill_text(self.text, self.location, self.text_color, self.clip_bounds.quad.bounds);
+        self.bounds.render(renderer);
+        renderer.fill_text(self.text, self.location, self.text_color, self.bounds.quad.bounds);
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Bounds {
-    quad: iced::advanced::renderer::Quad,
-    background: iced::Background,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl Bounds {
-    fn new(bounds: iced::Rectangle, color: iced::Color) -> Self {
-        Self {
-            quad: iced::advanced::renderer::Quad {
-                bounds,
-                border: iced::Border::default(),
-                shadow: iced::Shadow::default(),
-                snap: false,
-            },
-            background: iced::Background::Color(color),
-        }
+    fn build_max_frets(fret_spacing: FretSpacing, scale_length: Option<ScaleLength>) {
+        let tuning = Tuning {
+            total_frets: u8::MAX,
+            fret_spacing,
+            scale_length,
+            ..Tuning::default()
+        };
+        let bounds = iced::Rectangle::new(iced::Point::ORIGIN, iced::Size::new(1600.0, 400.0));
+        let cx = Cx::new(&tuning, bounds, LayoutOptions::default());
+        let positions: Vec<_> = (0..=u8::MAX)
+            .map(|x| cx.calculate_fret_position_x_at(x, cx.calculate_string_position_y(1)))
+            .collect();
+        assert!(positions.iter().all(|x| x.is_finite()));
+        assert!(positions.windows(2).all(|x| x[0] <= x[1]));
     }
 
-    fn with_border(mut self, value: iced::Border) -> Self {
-        self.quad.border = value;
-        self
+    #[test]
+    fn max_frets_even() {
+        build_max_frets(FretSpacing::Even, None);
+    }
+
+    #[test]
+    fn max_frets_realistic() {
+        build_max_frets(FretSpacing::Realistic, None);
     }
 
-    fn render(self, renderer: &mut impl iced::advanced::renderer::Renderer) {
-        renderer.fill_quad(self.quad, self.background);
+    #[test]
+    fn max_frets_fanned() {
+        let scale_length = ScaleLength {
+            bass: 27.0,
+            treble: 25.5,
+            neutral_fret: 7,
+        };
+        build_max_frets(FretSpacing::Realistic, Some(scale_length));
     }
 }
@@ -1,3 +1,4 @@
 mod fretboard;
+mod scene;
 
-pub use self::fretboard::Fretboard;
+pub use self::fretboard::{Fretboard, LabelMode, LabelModeError, Orientation, OrientationError};
@@ -0,0 +1,311 @@
+use std::fmt;
+
+#[derive(Debug, Default)]
+pub(super) struct Scene {
+    layers: Vec<Vec<Shape>>,
+}
+
+impl Scene {
+    pub(super) fn push(&mut self, shape: impl Into<Shape>) {
+        match self.layers.last_mut() {
+            Some(layer) => layer.push(shape.into()),
+            None => self.layers.push(vec![shape.into()]),
+        }
+    }
+
+    pub(super) fn push_layer(&mut self) {
+        self.layers.push(Vec::new());
+    }
+
+    pub(super) fn render<R>(self, renderer: &mut R, bounds: iced::Rectangle)
+    where
+        R: iced::advanced::graphics::geometry::Renderer + iced::advanced::text::Renderer<Font = iced::Font>,
+    {
+        // Geometry goes on top of quads within a layer, so the order is kept by layers only.
+        for (idx, layer) in self.layers.into_iter().enumerate() {
+            if idx == 0 {
+                layer.into_iter().for_each(|x| x.render(renderer));
+            } else {
+                renderer.with_layer(bounds, |renderer| layer.into_iter().for_each(|x| x.render(renderer)));
+            }
+        }
+    }
+
+    pub(super) fn to_svg(&self, size: iced::Size) -> String {
+        SvgDocument { scene: self, size }.to_string()
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum Shape {
+    Bounds(Bounds),
+    NoteLabel(NoteLabel),
+    Polygon(Polygon),
+    Segment(Segment),
+}
+
+impl Shape {
+    fn render<R>(self, renderer: &mut R)
+    where
+        R: iced::advanced::graphics::geometry::Renderer + iced::advanced::text::Renderer<Font = iced::Font>,
+    {
+        match self {
+            Self::Bounds(x) => x.render(renderer),
+            Self::NoteLabel(x) => x.render(renderer),
+            Self::Polygon(x) => x.render(renderer),
+            Self::Segment(x) => x.render(renderer),
+        }
+    }
+}
+
+impl From<Bounds> for Shape {
+    fn from(value: Bounds) -> Self {
+        Self::Bounds(value)
+    }
+}
+
+impl From<NoteLabel> for Shape {
+    fn from(value: NoteLabel) -> Self {
+        Self::NoteLabel(value)
+    }
+}
+
+impl From<Polygon> for Shape {
+    fn from(value: Polygon) -> Self {
+        Self::Polygon(value)
+    }
+}
+
+impl From<Segment> for Shape {
+    fn from(value: Segment) -> Self {
+        Self::Segment(value)
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bounds(x) => x.fmt(out),
+            Self::NoteLabel(x) => x.fmt(out),
+            Self::Polygon(x) => x.fmt(out),
+            Self::Segment(x) => x.fmt(out),
+        }
+    }
+}
+
+struct SvgDocument<'a> {
+    scene: &'a Scene,
+    size: iced::Size,
+}
+
+impl fmt::Display for SvgDocument<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let iced::Size { width, height } = self.size;
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#,
+        )?;
+        for shape in self.scene.layers.iter().flatten() {
+            writeln!(out, "{}", shape)?;
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct NoteLabel {
+    pub(super) clip_bounds: Bounds,
+    pub(super) location: iced::Point,
+    pub(super) text: iced::advanced::Text,
+    pub(super) text_color: iced::Color,
+}
+
+impl NoteLabel {
+    fn render(self, renderer: &mut impl iced::advanced::text::Renderer<Font = iced::Font>) {
+        self.clip_bounds.render(renderer);
+        renderer.fill_text(self.text, self.location, self.text_color, self.clip_bounds.quad.bounds);
+    }
+}
+
+impl fmt::Display for NoteLabel {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(out, "{}", self.clip_bounds)?;
+        write!(
+            out,
+            r#"<text x="{}" y="{}" font-family="monospace" font-size="{}" text-anchor="middle" dominant-baseline="central" {}>{}</text>"#,
+            self.location.x,
+            self.location.y,
+            self.text.size.0,
+            SvgPaint("fill", self.text_color),
+            SvgText(&self.text.content),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Segment {
+    pub(super) bounds: iced::Rectangle,
+    pub(super) color: iced::Color,
+    /// Lengths of a dash and of a gap between dashes, the line is solid when not set.
+    pub(super) dash: Option<[f32; 2]>,
+    pub(super) from: iced::Point,
+    pub(super) to: iced::Point,
+    pub(super) width: f32,
+}
+
+impl Segment {
+    fn render<R: iced::advanced::graphics::geometry::Renderer>(self, renderer: &mut R) {
+        let mut frame = iced::widget::canvas::Frame::with_bounds(renderer, self.bounds);
+        let mut stroke = iced::widget::canvas::Stroke::default()
+            .with_color(self.color)
+            .with_width(self.width);
+        if let Some(dash) = &self.dash {
+            stroke.line_dash = iced::widget::canvas::LineDash {
+                segments: dash,
+                offset: 0,
+            };
+        }
+        frame.stroke(&iced::widget::canvas::Path::line(self.from, self.to), stroke);
+        renderer.draw_geometry(frame.into_geometry());
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke-width="{}" {}"#,
+            self.from.x,
+            self.from.y,
+            self.to.x,
+            self.to.y,
+            self.width,
+            SvgPaint("stroke", self.color),
+        )?;
+        if let Some([dash, gap]) = self.dash {
+            write!(out, r#" stroke-dasharray="{} {}""#, dash, gap)?;
+        }
+        write!(out, "/>")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Polygon {
+    pub(super) bounds: iced::Rectangle,
+    pub(super) color: iced::Color,
+    pub(super) points: Vec<iced::Point>,
+}
+
+impl Polygon {
+    fn render<R: iced::advanced::graphics::geometry::Renderer>(self, renderer: &mut R) {
+        let mut frame = iced::widget::canvas::Frame::with_bounds(renderer, self.bounds);
+        let path = iced::widget::canvas::Path::new(|builder| {
+            for (idx, point) in self.points.iter().enumerate() {
+                if idx == 0 {
+                    builder.move_to(*point);
+                } else {
+                    builder.line_to(*point);
+                }
+            }
+            builder.close();
+        });
+        frame.fill(&path, self.color);
+        renderer.draw_geometry(frame.into_geometry());
+    }
+}
+
+impl fmt::Display for Polygon {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(out, r#"<polygon points=""#)?;
+        for (idx, point) in self.points.iter().enumerate() {
+            if idx > 0 {
+                write!(out, " ")?;
+            }
+            write!(out, "{},{}", point.x, point.y)?;
+        }
+        write!(out, r#"" {}/>"#, SvgPaint("fill", self.color))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Bounds {
+    pub(super) quad: iced::advanced::renderer::Quad,
+    color: iced::Color,
+}
+
+impl Bounds {
+    pub(super) fn new(bounds: iced::Rectangle, color: iced::Color) -> Self {
+        Self {
+            quad: iced::advanced::renderer::Quad {
+                bounds,
+                border: iced::Border::default(),
+                shadow: iced::Shadow::default(),
+                snap: false,
+            },
+            color,
+        }
+    }
+
+    pub(super) fn with_border(mut self, value: iced::Border) -> Self {
+        self.quad.border = value;
+        self
+    }
+
+    pub(super) fn render(self, renderer: &mut impl iced::advanced::renderer::Renderer) {
+        renderer.fill_quad(self.quad, iced::Background::Color(self.color));
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let iced::Rectangle { x, y, width, height } = self.quad.bounds;
+        let border = self.quad.border;
+        // SVG strokes are centered on the edge while iced borders go inside of the bounds.
+        let inset = border.width / 2.0;
+        write!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {}"#,
+            x + inset,
+            y + inset,
+            (width - border.width).max(0.0),
+            (height - border.width).max(0.0),
+            (border.radius.top_left - inset).max(0.0),
+            SvgPaint("fill", self.color),
+        )?;
+        if border.width > 0.0 {
+            write!(
+                out,
+                r#" stroke-width="{}" {}"#,
+                border.width,
+                SvgPaint("stroke", border.color)
+            )?;
+        }
+        write!(out, "/>")
+    }
+}
+
+struct SvgPaint(&'static str, iced::Color);
+
+impl fmt::Display for SvgPaint {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let [r, g, b, _] = self.1.into_rgba8();
+        write!(
+            out,
+            r##"{}="#{:02x}{:02x}{:02x}" {}-opacity="{}""##,
+            self.0, r, g, b, self.0, self.1.a
+        )
+    }
+}
+
+struct SvgText<'a>(&'a str);
+
+impl fmt::Display for SvgText<'_> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        self.0.chars().try_for_each(|x| match x {
+            '&' => write!(out, "&amp;"),
+            '<' => write!(out, "&lt;"),
+            '>' => write!(out, "&gt;"),
+            x => write!(out, "{}", x),
+        })
+    }
+}